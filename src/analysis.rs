@@ -0,0 +1,246 @@
+//! Structural measurements of a maze, independent of any solving algorithm.
+
+use std::collections::HashSet;
+
+use crate::{
+    distance_field, get_end, get_start, is_blocked, manhattan_dist, DIRECTIONS, END, START, WALL,
+};
+
+/// Fraction of all cells that are walls.
+pub fn wall_density(maze: &[Vec<char>]) -> f64 {
+    let cells = maze.iter().map(Vec::len).sum::<usize>();
+    let walls = maze.iter().flatten().filter(|&&c| c == WALL).count();
+    walls as f64 / cells as f64
+}
+
+fn open_cells(maze: &[Vec<char>]) -> impl Iterator<Item = (usize, usize)> + '_ {
+    maze.iter().enumerate().flat_map(|(row, cells)| {
+        cells
+            .iter()
+            .enumerate()
+            .filter(|&(_, &c)| !is_blocked(c))
+            .map(move |(col, _)| (row, col))
+    })
+}
+
+/// Number of open cells orthogonally adjacent to `(row, col)`. One-way cells
+/// count as open whichever way they point.
+fn degree(maze: &[Vec<char>], (row, col): (usize, usize)) -> usize {
+    DIRECTIONS
+        .iter()
+        .map(|&(dr, dc)| (row.wrapping_add(dr), col.wrapping_add(dc)))
+        .filter(|&(r, c)| {
+            maze.get(r)
+                .and_then(|cells| cells.get(c))
+                .is_some_and(|&c| !is_blocked(c))
+        })
+        .count()
+}
+
+/// Every open cell other than the endpoints with exactly one open neighbour,
+/// in row-major order.
+pub fn dead_ends(maze: &[Vec<char>]) -> Vec<(usize, usize)> {
+    open_cells(maze)
+        .filter(|&(row, col)| !matches!(maze[row][col], START | END))
+        .filter(|&cell| degree(maze, cell) == 1)
+        .collect()
+}
+
+/// Number of orthogonally connected regions of open cells (anything but
+/// walls and danger zones).
+pub fn component_count(maze: &[Vec<char>]) -> usize {
+    let mut seen = HashSet::new();
+    let mut count = 0;
+    for cell in open_cells(maze) {
+        if seen.contains(&cell) {
+            continue;
+        }
+        count += 1;
+        seen.extend(distance_field(maze, cell).into_keys());
+    }
+    count
+}
+
+/// Number of open cells reachable from the start, the start included.
+pub fn reachable_cells(maze: &[Vec<char>]) -> usize {
+    get_start(maze).map_or(0, |start| distance_field(maze, start).len())
+}
+
+/// Length in steps of the shortest path from the start to the end, if any.
+pub fn shortest_path_length(maze: &[Vec<char>]) -> Option<usize> {
+    let end = get_end(maze)?;
+    distance_field(maze, get_start(maze)?).get(&end).copied()
+}
+
+/// How much the shortest path winds: its length divided by the manhattan
+/// distance between the endpoints. 1.0 is a straight shot, unsolvable mazes
+/// have no complexity.
+pub fn complexity(maze: &[Vec<char>]) -> Option<f64> {
+    let direct = manhattan_dist(get_start(maze)?, get_end(maze)?);
+    shortest_path_length(maze).map(|len| len as f64 / direct.max(1) as f64)
+}
+
+/// Every measurement above, gathered in one pass over the crate's analysis functions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analysis {
+    pub rows: usize,
+    pub cols: usize,
+    pub wall_density: f64,
+    pub components: usize,
+    pub open_cells: usize,
+    pub reachable_cells: usize,
+    pub solvable: bool,
+    pub shortest_path_length: Option<usize>,
+    pub complexity: Option<f64>,
+}
+
+impl Analysis {
+    pub fn of(maze: &[Vec<char>]) -> Self {
+        let shortest_path_length = shortest_path_length(maze);
+        Self {
+            rows: maze.len(),
+            cols: maze.first().map_or(0, Vec::len),
+            wall_density: wall_density(maze),
+            components: component_count(maze),
+            open_cells: open_cells(maze).count(),
+            reachable_cells: reachable_cells(maze),
+            solvable: shortest_path_length.is_some(),
+            shortest_path_length,
+            complexity: complexity(maze),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let opt = |v: Option<String>| v.unwrap_or_else(|| "null".into());
+        format!(
+            "{{\"rows\":{},\"cols\":{},\"wall_density\":{:.4},\"components\":{},\"open_cells\":{},\"reachable_cells\":{},\"solvable\":{},\"shortest_path_length\":{},\"complexity\":{}}}",
+            self.rows,
+            self.cols,
+            self.wall_density,
+            self.components,
+            self.open_cells,
+            self.reachable_cells,
+            self.solvable,
+            opt(self.shortest_path_length.map(|l| l.to_string())),
+            opt(self.complexity.map(|c| format!("{c:.4}"))),
+        )
+    }
+}
+
+impl std::fmt::Display for Analysis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "size:                 {}x{}", self.rows, self.cols)?;
+        writeln!(f, "wall density:         {:.4}", self.wall_density)?;
+        writeln!(f, "components:           {}", self.components)?;
+        writeln!(
+            f,
+            "reachable cells:      {}/{}",
+            self.reachable_cells, self.open_cells
+        )?;
+        writeln!(f, "solvable:             {}", self.solvable)?;
+        match (self.shortest_path_length, self.complexity) {
+            (Some(len), Some(complexity)) => {
+                writeln!(f, "shortest path length: {len}")?;
+                write!(f, "complexity:           {complexity:.4}")
+            }
+            _ => {
+                writeln!(f, "shortest path length: none")?;
+                write!(f, "complexity:           none")
+            }
+        }
+    }
+}
+
+/// Length of the run of open cells through `cell` along one axis, `cell` included.
+fn run_length(maze: &[Vec<char>], (row, col): (usize, usize), vertical: bool) -> usize {
+    let open = |r: usize, c: usize| !is_blocked(maze[r][c]);
+    if vertical {
+        let up = (0..row).rev().take_while(|&r| open(r, col)).count();
+        let down = (row + 1..maze.len()).take_while(|&r| open(r, col)).count();
+        up + 1 + down
+    } else {
+        let left = (0..col).rev().take_while(|&c| open(row, c)).count();
+        let right = (col + 1..maze[row].len())
+            .take_while(|&c| open(row, c))
+            .count();
+        left + 1 + right
+    }
+}
+
+/// The corridor width at each cell of `path`: the number of open cells in a
+/// line through it, perpendicular to the direction of travel. A horizontal
+/// step measures the vertical run and vice versa. Where the path turns or
+/// steps diagonally (or, at the endpoints, where only one step is known and
+/// it is ambiguous) the narrower of the two runs is used, since the traveller
+/// has to fit through both.
+pub fn corridor_widths(maze: &[Vec<char>], path: &[(usize, usize)]) -> Vec<usize> {
+    // `Some(true)` for a horizontal step, `Some(false)` for a vertical one.
+    let axis = |a: (usize, usize), b: (usize, usize)| match (a.0 == b.0, a.1 == b.1) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
+    };
+    (0..path.len())
+        .map(|i| {
+            let steps = [
+                i.checked_sub(1).map(|p| axis(path[p], path[i])),
+                path.get(i + 1).map(|&next| axis(path[i], next)),
+            ];
+            let vertical_run = run_length(maze, path[i], true);
+            let horizontal_run = run_length(maze, path[i], false);
+            match steps {
+                [Some(Some(true)), Some(Some(true)) | None] | [None, Some(Some(true))] => {
+                    vertical_run
+                }
+                [Some(Some(false)), Some(Some(false)) | None] | [None, Some(Some(false))] => {
+                    horizontal_run
+                }
+                _ => vertical_run.min(horizontal_run),
+            }
+        })
+        .collect()
+}
+
+/// The narrowest corridor width along `path`, see [`corridor_widths`].
+pub fn min_corridor_width(maze: &[Vec<char>], path: &[(usize, usize)]) -> Option<usize> {
+    corridor_widths(maze, path).into_iter().min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_maze;
+
+    #[test]
+    fn analysis_of_a_maze_with_a_pocket() {
+        let maze = parse_maze("######\n#A  ##\n### B#\n# ####\n######");
+        assert_eq!(
+            Analysis::of(&maze),
+            Analysis {
+                rows: 5,
+                cols: 6,
+                wall_density: 0.8,
+                components: 2,
+                open_cells: 6,
+                reachable_cells: 5,
+                solvable: true,
+                shortest_path_length: Some(4),
+                complexity: Some(1.0),
+            }
+        );
+    }
+
+    #[test]
+    fn a_single_cell_pinch_has_width_one() {
+        let maze = parse_maze("#######\n#  #  #\n#A   B#\n#  #  #\n#######");
+        let path = (1..=5).map(|col| (2, col)).collect::<Vec<_>>();
+        assert_eq!(corridor_widths(&maze, &path), [3, 3, 1, 3, 3]);
+        assert_eq!(min_corridor_width(&maze, &path), Some(1));
+    }
+
+    #[test]
+    fn dead_ends_of_a_known_maze() {
+        let maze = parse_maze("#######\n#A  # #\n### # #\n# #   #\n#    B#\n#######");
+        assert_eq!(dead_ends(&maze), [(1, 5), (3, 1)]);
+    }
+}
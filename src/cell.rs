@@ -0,0 +1,168 @@
+//! Parsing that keeps each cell's original glyph next to its meaning, so a
+//! maze can be solved on the normalised grid and still be rendered back with
+//! exactly the characters it was written in.
+
+use crate::{is_maze_valid, parse_maze, Error, DANGER, END, ONE_WAY, START, WALL};
+
+/// What a cell means to the solvers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharClass {
+    Wall,
+    Open,
+    Start,
+    End,
+    Danger,
+    /// A one-way cell; the payload is the arrow it was written as.
+    OneWay(char),
+    /// Open terrain that costs the payload (1 to 9) to enter.
+    Terrain(u8),
+}
+
+impl CharClass {
+    /// The class of a parsed grid character, `None` if it is not valid.
+    pub fn of(c: char) -> Option<Self> {
+        match c {
+            WALL => Some(CharClass::Wall),
+            ' ' => Some(CharClass::Open),
+            START => Some(CharClass::Start),
+            END => Some(CharClass::End),
+            DANGER => Some(CharClass::Danger),
+            c if ONE_WAY.contains(&c) => Some(CharClass::OneWay(c)),
+            '1'..='9' => Some(CharClass::Terrain(c as u8 - b'0')),
+            _ => None,
+        }
+    }
+
+    /// The character the solvers expect for this class.
+    pub fn grid_char(self) -> char {
+        match self {
+            CharClass::Wall => WALL,
+            CharClass::Open => ' ',
+            CharClass::Start => START,
+            CharClass::End => END,
+            CharClass::Danger => DANGER,
+            CharClass::OneWay(c) => c,
+            CharClass::Terrain(cost) => (b'0' + cost) as char,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    /// The character as it appeared in the input.
+    pub original: char,
+    pub class: CharClass,
+}
+
+/// Parses `input` like `parse_maze`, validating it, but keeps every cell's
+/// original character alongside its class.
+pub fn parse_maze_annotated(input: &str) -> Result<Vec<Vec<Cell>>, Error> {
+    let grid = parse_maze(input);
+    is_maze_valid(&grid)?;
+    Ok(input
+        .lines()
+        .zip(&grid)
+        .map(|(line, row)| {
+            line.chars()
+                .zip(row)
+                .map(|(original, &c)| Cell {
+                    original,
+                    class: CharClass::of(c).expect("validated above"),
+                })
+                .collect()
+        })
+        .collect())
+}
+
+/// The grid of class characters the solvers work on.
+pub fn to_grid(cells: &[Vec<Cell>]) -> Vec<Vec<char>> {
+    cells
+        .iter()
+        .map(|row| row.iter().map(|cell| cell.class.grid_char()).collect())
+        .collect()
+}
+
+/// Renders `solved` (a grid produced from `to_grid(cells)` and then solved)
+/// using the original glyphs everywhere the solver did not leave a mark.
+pub fn render_annotated(cells: &[Vec<Cell>], solved: &[Vec<char>]) -> String {
+    cells
+        .iter()
+        .zip(solved)
+        .map(|(row, solved_row)| {
+            row.iter()
+                .zip(solved_row)
+                .map(|(cell, &c)| match c == cell.class.grid_char() {
+                    true => cell.original,
+                    false => c,
+                })
+                .chain(['\n'])
+                .collect::<String>()
+        })
+        .collect()
+}
+
+/// The class of a token in a token grid. A token is one character repeated
+/// any number of times; `W` or `#` is a wall, `.` is open and anything else
+/// means what that character means in an ordinary maze (`AA`, `BB`, `DD`,
+/// `>>` and so on).
+fn token_class(token: &str) -> Option<CharClass> {
+    let mut chars = token.chars();
+    let c = chars.next()?;
+    if !chars.all(|other| other == c) {
+        return None;
+    }
+    match c {
+        'W' | '#' => Some(CharClass::Wall),
+        '.' => Some(CharClass::Open),
+        c => CharClass::of(c),
+    }
+}
+
+/// Parses a maze written as whitespace separated tokens, one per cell (for
+/// example `WW .. AA BB`), into the grid the solvers work on, validating it.
+pub fn parse_token_grid(input: &str) -> Result<Vec<Vec<char>>, Error> {
+    let grid = input
+        .lines()
+        .map(|line| {
+            line.split_whitespace()
+                .map(|token| token_class(token).map(CharClass::grid_char))
+                .collect::<Option<Vec<char>>>()
+                .ok_or(Error::InvalidCharacter)
+        })
+        .collect::<Result<Vec<Vec<char>>, Error>>()?;
+    is_maze_valid(&grid)?;
+    Ok(grid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{maze_solver, Algorithm};
+
+    #[test]
+    fn annotated_render_keeps_the_original_glyphs() {
+        let input = "#█###\n#A >#\n#3 B#\n█####\n";
+        let cells = parse_maze_annotated(input).unwrap();
+        let grid = to_grid(&cells);
+        assert_eq!(render_annotated(&cells, &grid), input);
+
+        let mut solved = grid.clone();
+        maze_solver(&mut solved, Algorithm::BFS, false).unwrap();
+        assert_eq!(
+            render_annotated(&cells, &solved),
+            "#█###\n#A >#\n#**B#\n█####\n"
+        );
+    }
+
+    #[test]
+    fn token_grids_parse_and_solve() {
+        let input = "WW WW WW WW WW\nWW AA .. 33 WW\nWW .. >> BB WW\nWW WW WW WW WW\n";
+        let mut grid = parse_token_grid(input).unwrap();
+        assert_eq!(grid[1], [WALL, START, ' ', '3', WALL]);
+        assert_eq!(grid[2], [WALL, ' ', '>', END, WALL]);
+        maze_solver(&mut grid, Algorithm::AStar, false).unwrap();
+        assert_eq!(grid[2][2], '*');
+
+        assert_eq!(parse_token_grid("AA .B BB"), Err(Error::InvalidCharacter));
+    }
+}
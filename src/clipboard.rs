@@ -0,0 +1,58 @@
+//! Copying text to the system clipboard by handing it to whichever clipboard
+//! tool the platform has: `pbcopy` on macOS, `clip` on Windows and
+//! `wl-copy`, `xclip` or `xsel` elsewhere, tried in that order.
+
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use crate::Error;
+
+const TOOLS: &[(&str, &[&str])] = &[
+    ("pbcopy", &[]),
+    ("clip", &[]),
+    ("wl-copy", &[]),
+    ("xclip", &["-selection", "clipboard"]),
+    ("xsel", &["--clipboard", "--input"]),
+];
+
+fn copy_with(program: &str, args: &[&str], text: &str) -> Option<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    let written = child.stdin.take()?.write_all(text.as_bytes()).is_ok();
+    (child.wait().ok()?.success() && written).then_some(())
+}
+
+/// Replaces the clipboard contents with `text`.
+pub fn copy(text: &str) -> Result<(), Error> {
+    TOOLS
+        .iter()
+        .find_map(|(program, args)| copy_with(program, args, text))
+        .ok_or(Error::ClipboardFailed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{maze_solver, parse_maze, render, Algorithm};
+
+    #[test]
+    fn the_tool_receives_the_rendered_maze() {
+        let mut maze = parse_maze("#####\n#A  #\n#  B#\n#####");
+        maze_solver(&mut maze, Algorithm::BFS, false).unwrap();
+        let text = render(&maze);
+        let out = std::env::temp_dir().join(format!("clipboard-test-{}", std::process::id()));
+        let script = format!("cat > '{}'", out.display());
+        assert_eq!(copy_with("sh", &["-c", &script], &text), Some(()));
+        assert_eq!(std::fs::read_to_string(&out).unwrap(), text);
+        std::fs::remove_file(&out).unwrap();
+
+        assert_eq!(copy_with("false", &[], &text), None);
+    }
+}
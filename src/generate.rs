@@ -0,0 +1,132 @@
+//! Random maze generation with a growing tree: carve from a cell on a stack
+//! of open cells into an uncarved neighbour, backing off once a cell has none
+//! left. Always taking the newest cell gives long winding corridors, taking a
+//! random one gives many short branches. Difficulty picks the mix, how many
+//! extra walls are knocked out to make loops and where the end goes.
+
+use crate::{distance_field, rng::Rng, DIRECTIONS, END, START, WALL};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+    /// Long corridors with loops between them and the end in the far corner.
+    Easy,
+    #[default]
+    Medium,
+    /// Lots of branches and dead ends, no loops, and the end on the cell
+    /// furthest from the start.
+    Hard,
+}
+
+impl Difficulty {
+    /// Chance of growing from a random open cell instead of the newest one.
+    fn branching(self) -> f64 {
+        match self {
+            Difficulty::Easy => 0.0,
+            Difficulty::Medium => 0.25,
+            Difficulty::Hard => 0.6,
+        }
+    }
+
+    /// Chance of knocking out each remaining wall between two corridors.
+    fn loops(self) -> f64 {
+        match self {
+            Difficulty::Easy => 0.2,
+            Difficulty::Medium => 0.05,
+            Difficulty::Hard => 0.0,
+        }
+    }
+}
+
+/// The highest odd index inside the outer walls of a side of length `n`.
+fn last_corridor(n: usize) -> usize {
+    (n - 2) - (n - 1) % 2
+}
+
+/// A `rows` x `cols` maze with walls all around, the start in the top-left
+/// corridor cell and the end placed according to `difficulty`. Corridors run
+/// along odd rows and columns, so an even size leaves an extra wall along the
+/// bottom or right. Sizes below 5 are rounded up to 5.
+pub fn generate(rows: usize, cols: usize, difficulty: Difficulty, rng: &mut Rng) -> Vec<Vec<char>> {
+    let (rows, cols) = (rows.max(5), cols.max(5));
+    let mut maze = vec![vec![WALL; cols]; rows];
+    // Corridor cells sit two apart with the wall between them at the midpoint.
+    let step = |(row, col): (usize, usize), (dr, dc): (usize, usize)| {
+        let next = (
+            row.wrapping_add(dr.wrapping_mul(2)),
+            col.wrapping_add(dc.wrapping_mul(2)),
+        );
+        (next.0 > 0 && next.0 < rows - 1 && next.1 > 0 && next.1 < cols - 1)
+            .then_some((next, (row.wrapping_add(dr), col.wrapping_add(dc))))
+    };
+
+    maze[1][1] = ' ';
+    let mut open = vec![(1, 1)];
+    while !open.is_empty() {
+        let i = match rng.chance(difficulty.branching()) {
+            true => rng.below(open.len()),
+            false => open.len() - 1,
+        };
+        let mut options = DIRECTIONS
+            .iter()
+            .filter_map(|&d| step(open[i], d))
+            .filter(|&((row, col), _)| maze[row][col] == WALL)
+            .collect::<Vec<_>>();
+        if options.is_empty() {
+            open.remove(i);
+            continue;
+        }
+        rng.shuffle(&mut options);
+        let ((row, col), (wall_row, wall_col)) = options[0];
+        maze[wall_row][wall_col] = ' ';
+        maze[row][col] = ' ';
+        open.push((row, col));
+    }
+
+    for row in 1..rows - 1 {
+        for col in 1..cols - 1 {
+            let horizontal = maze[row][col - 1] == ' ' && maze[row][col + 1] == ' ';
+            let vertical = maze[row - 1][col] == ' ' && maze[row + 1][col] == ' ';
+            if maze[row][col] == WALL
+                && (row + col) % 2 == 1
+                && (horizontal || vertical)
+                && rng.chance(difficulty.loops())
+            {
+                maze[row][col] = ' ';
+            }
+        }
+    }
+
+    maze[1][1] = START;
+    let end = match difficulty {
+        Difficulty::Hard => distance_field(&maze, (1, 1))
+            .into_iter()
+            .max_by_key(|&(cell, d)| (d, cell))
+            .map(|(cell, _)| cell)
+            .unwrap(),
+        _ => (last_corridor(rows), last_corridor(cols)),
+    };
+    maze[end.0][end.1] = END;
+    maze
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{analysis, is_maze_valid};
+
+    #[test]
+    fn hard_mazes_have_more_dead_ends() {
+        for seed in 0..5 {
+            let maze = |difficulty| generate(31, 41, difficulty, &mut Rng::new(seed));
+            let (easy, hard) = (maze(Difficulty::Easy), maze(Difficulty::Hard));
+            for maze in [&easy, &hard] {
+                assert_eq!(is_maze_valid(maze), Ok(()));
+                assert!(analysis::shortest_path_length(maze).is_some());
+            }
+            assert!(
+                analysis::dead_ends(&hard).len() > analysis::dead_ends(&easy).len(),
+                "seed {seed}"
+            );
+        }
+    }
+}
@@ -0,0 +1,2170 @@
+pub mod analysis;
+pub mod cell;
+#[cfg(feature = "clipboard")]
+pub mod clipboard;
+pub mod generate;
+pub mod json;
+#[cfg(all(unix, target_pointer_width = "64"))]
+pub mod mmap;
+pub mod profile;
+pub mod rng;
+pub mod searcher;
+pub mod symmetry;
+#[cfg(feature = "tui")]
+pub mod tui;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, HashSet, VecDeque},
+    io::Read,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    InvalidAlgorithm,
+    InvalidCharacter,
+    ReservedCharacter(char),
+    FileNotFound,
+    MangledRows,
+    StartNotFound,
+    EndNotFound,
+    EmptyMaze,
+    InvalidArgument,
+    CheckpointFailed,
+    CorruptPath,
+    FeatureDisabled,
+    TerminalFailed,
+    MmapFailed,
+    Nondeterministic,
+    OutputFailed,
+    OutOfBounds,
+    DuplicateStart,
+    DuplicateEnd,
+    DirectionalAsymmetry,
+    AuditFailed,
+    ClipboardFailed,
+    NoPathFound,
+    /// A `Charset` gives two roles the same character, or picks one that
+    /// already means something else.
+    ConflictingCharacter(char),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Solved,
+    Unsolvable,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    DFS,
+    BFS,
+    GreedyBestFirst,
+    AStar,
+}
+
+impl Algorithm {
+    pub const ALL: [Algorithm; 4] = [
+        Algorithm::BFS,
+        Algorithm::AStar,
+        Algorithm::DFS,
+        Algorithm::GreedyBestFirst,
+    ];
+
+    /// The name the command line uses for this algorithm.
+    pub fn name(self) -> &'static str {
+        match self {
+            Algorithm::DFS => "DFS",
+            Algorithm::BFS => "BFS",
+            Algorithm::GreedyBestFirst => "GBFS",
+            Algorithm::AStar => "A*",
+        }
+    }
+
+    /// Whether the algorithm guarantees a shortest path.
+    pub fn is_optimal(self) -> bool {
+        matches!(self, Algorithm::BFS | Algorithm::AStar)
+    }
+}
+
+const VALID_CHARS: &str = "AB█ ^<v>D123456789";
+/// One-way cells, in `DIRECTIONS` order. Each can only be entered while moving
+/// in the direction it points, so `'>'` is entered from its left neighbour.
+pub const ONE_WAY: [char; 4] = ['^', '<', 'v', '>'];
+/// Marks the solvers paint onto the grid (`'@'` visited, `'*'` path). They are
+/// rejected in input so a solved cell can never be confused with the maze itself.
+const RESERVED_CHARS: &str = "@*";
+/// Danger zones: the path must never enter them, so they are routed around
+/// exactly like walls, but they keep their own glyph in the output.
+pub const DANGER: char = 'D';
+pub const START: char = 'A';
+pub const END: char = 'B';
+/// Walls, which input may also write as `'#'`.
+pub const WALL: char = '█';
+pub type Path = Vec<(usize, usize)>;
+pub type Visited = HashSet<(usize, usize)>;
+/// Cells in the order a search expanded them.
+type Expanded = Vec<(usize, usize)>;
+/// Why each cell a search left untouched was not expanded.
+pub type Diagnosis = HashMap<(usize, usize), PruneReason>;
+/// Each cell mapped to the cells it leads on to.
+pub type Dag = HashMap<(usize, usize), Vec<(usize, usize)>>;
+/// Decides whether the cell at `(row, col)` may be entered.
+pub type Passable<'a> = &'a dyn Fn((usize, usize)) -> bool;
+
+const DIRECTIONS: [(usize, usize); 4] = [(usize::MAX, 0), (0, usize::MAX), (1, 0), (0, 1)];
+/// `DIRECTIONS` followed by the four diagonals.
+const EIGHT_DIRECTIONS: [(usize, usize); 8] = [
+    (usize::MAX, 0),
+    (0, usize::MAX),
+    (1, 0),
+    (0, 1),
+    (usize::MAX, usize::MAX),
+    (usize::MAX, 1),
+    (1, usize::MAX),
+    (1, 1),
+];
+
+/// Which neighbours a search may step to.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Up, left, down and right.
+    #[default]
+    Four,
+    /// The four orthogonal neighbours plus the diagonals, each one step. A
+    /// diagonal step may not squeeze between two blocked cells that touch at
+    /// a corner; at least one of the two cells beside it has to be open.
+    Eight,
+}
+
+impl Connectivity {
+    fn directions(self) -> &'static [(usize, usize)] {
+        match self {
+            Connectivity::Four => &DIRECTIONS,
+            Connectivity::Eight => &EIGHT_DIRECTIONS,
+        }
+    }
+
+    /// The fewest steps between two cells on an open grid: manhattan distance
+    /// with four neighbours, Chebyshev distance with eight. Never more than
+    /// the real distance, so it keeps A* admissible.
+    fn distance(self, p1: (usize, usize), p2: (usize, usize)) -> usize {
+        match self {
+            Connectivity::Four => manhattan_dist(p1, p2),
+            Connectivity::Eight => p1.0.abs_diff(p2.0).max(p1.1.abs_diff(p2.1)),
+        }
+    }
+}
+
+/// The cost of stepping onto a cell: the digit for terrain cells `'1'` to
+/// `'9'`, 1 for everything else. Only greedy best first and A* look at it;
+/// DFS and BFS treat terrain as ordinary open cells.
+pub fn cell_cost(c: char) -> usize {
+    match c {
+        '1'..='9' => c as usize - '0' as usize,
+        _ => 1,
+    }
+}
+
+/// Whether a cell can never be entered: walls and danger zones.
+pub fn is_blocked(c: char) -> bool {
+    c == WALL || c == DANGER
+}
+
+/// The characters a maze may contain once parsed (`'#'` in input files is read
+/// as `WALL`). Downstream code can use this to validate its own grids.
+pub fn valid_chars() -> &'static str {
+    VALID_CHARS
+}
+
+pub fn is_maze_valid(maze: &[Vec<char>]) -> Result<(), Error> {
+    if maze.is_empty() {
+        Err(Error::EmptyMaze)
+    } else if !maze.iter().all(|row| row.len() == maze[0].len()) {
+        Err(Error::MangledRows)
+    } else if get_start(maze).is_none() {
+        Err(Error::StartNotFound)
+    } else if get_end(maze).is_none() {
+        Err(Error::EndNotFound)
+    } else if let Some(c) = maze
+        .iter()
+        .flatten()
+        .copied()
+        .find(|c| RESERVED_CHARS.contains(*c))
+    {
+        Err(Error::ReservedCharacter(c))
+    } else if !maze
+        .iter()
+        .all(|row| row.iter().all(|&c| valid_chars().contains(c)))
+    {
+        Err(Error::InvalidCharacter)
+    } else {
+        Ok(())
+    }
+}
+
+/// Sets the cell at `(row, col)` to `c`, re-validating only what the edit can
+/// affect: `c` itself, and the start/end counts when an endpoint is placed or
+/// overwritten. On error the maze is left unchanged.
+pub fn set_cell(maze: &mut [Vec<char>], row: usize, col: usize, c: char) -> Result<(), Error> {
+    let c = if c == '#' { WALL } else { c };
+    let old = *maze
+        .get(row)
+        .and_then(|cells| cells.get(col))
+        .ok_or(Error::OutOfBounds)?;
+    if RESERVED_CHARS.contains(c) {
+        return Err(Error::ReservedCharacter(c));
+    }
+    if !valid_chars().contains(c) {
+        return Err(Error::InvalidCharacter);
+    }
+    if old != c {
+        let elsewhere = |target: char| {
+            maze.iter().enumerate().any(|(r, cells)| {
+                cells
+                    .iter()
+                    .enumerate()
+                    .any(|(k, &cell)| cell == target && (r, k) != (row, col))
+            })
+        };
+        match (old, c) {
+            (_, START) if elsewhere(START) => return Err(Error::DuplicateStart),
+            (_, END) if elsewhere(END) => return Err(Error::DuplicateEnd),
+            (START, _) if !elsewhere(START) => return Err(Error::StartNotFound),
+            (END, _) if !elsewhere(END) => return Err(Error::EndNotFound),
+            _ => {}
+        }
+    }
+    maze[row][col] = c;
+    Ok(())
+}
+
+pub fn get_start(maze: &[Vec<char>]) -> Option<(usize, usize)> {
+    for (rowi, row) in maze.iter().enumerate() {
+        for (coli, ele) in row.iter().copied().enumerate() {
+            if ele == START {
+                return Some((rowi, coli));
+            }
+        }
+    }
+    None
+}
+
+pub fn get_end(maze: &[Vec<char>]) -> Option<(usize, usize)> {
+    for (rowi, row) in maze.iter().enumerate() {
+        for (coli, ele) in row.iter().copied().enumerate() {
+            if ele == END {
+                return Some((rowi, coli));
+            }
+        }
+    }
+    None
+}
+
+/// Where a search may stop: any cell within `radius` steps of `end`, and how
+/// the search may move to get there. A radius of 0 is the usual single end
+/// cell.
+#[derive(Debug, Clone, Copy)]
+struct Goal {
+    end: (usize, usize),
+    radius: usize,
+    connectivity: Connectivity,
+}
+
+impl Goal {
+    fn reached(self, cell: (usize, usize)) -> bool {
+        self.connectivity.distance(cell, self.end) <= self.radius
+    }
+
+    /// Admissible distance to the nearest cell of the region.
+    fn distance(self, cell: (usize, usize)) -> usize {
+        self.connectivity
+            .distance(cell, self.end)
+            .saturating_sub(self.radius)
+    }
+}
+
+/// Marks a cell as part of the path unless it is one of the endpoints.
+fn mark_path_cell(maze: &mut [Vec<char>], (row, col): (usize, usize)) {
+    if !matches!(maze[row][col], START | END) {
+        maze[row][col] = '*';
+    }
+}
+
+/// The state of a depth first search: the chain of cells from the start
+/// currently being explored, every cell entered so far and the expansion
+/// order.
+#[derive(Default)]
+struct DfsState {
+    path: Path,
+    vis: HashSet<(usize, usize)>,
+    expanded: Expanded,
+    /// The longest the chain has been.
+    max_depth: usize,
+}
+
+/// Depth first search with an explicit stack, so arbitrarily long corridors
+/// cannot overflow the call stack. Neighbours are tried in direction order,
+/// the same order a recursive search would try them in.
+fn dfs(
+    maze: &[Vec<char>],
+    start: (usize, usize),
+    state: &mut DfsState,
+    is_passable: Passable,
+    goal: Goal,
+) -> bool {
+    let directions = goal.connectivity.directions();
+    // The next direction to try from each cell of `state.path`.
+    let mut tried: Vec<usize> = Vec::new();
+    let mut entering = Some(start);
+    loop {
+        if let Some(cell) = entering.take() {
+            let open = profile::time("dfs", "neighbours", || {
+                cell.0 < maze.len()
+                    && cell.1 < maze[cell.0].len()
+                    && is_passable(cell)
+                    && state.vis.insert(cell)
+            });
+            if open {
+                profile::time("dfs", "frontier", || {
+                    state.path.push(cell);
+                    tried.push(0);
+                });
+                state.max_depth = state.max_depth.max(state.path.len());
+                if goal.reached(cell) {
+                    return true;
+                }
+                state.expanded.push(cell);
+            }
+        }
+
+        let Some(&d) = tried.last() else {
+            return false;
+        };
+        if d == directions.len() {
+            profile::time("dfs", "frontier", || {
+                tried.pop();
+                state.path.pop();
+            });
+            continue;
+        }
+        let depth = tried.len();
+        tried[depth - 1] = d + 1;
+        let (row, col) = state.path[depth - 1];
+        entering = profile::time("dfs", "neighbours", || {
+            let (dx, dy) = directions[d];
+            let next = (row.overflowing_add(dx).0, col.overflowing_add(dy).0);
+            (can_enter(maze, next, d) && !cuts_corner(maze, (row, col), d, is_passable))
+                .then_some(next)
+        });
+    }
+}
+
+/// Walks `came_from` back from `end` to `start`, returning the path in order
+/// from `start` to `end`. A parent map that revisits a cell or dead-ends before
+/// reaching `start` is a solver bug and is reported as `Error::CorruptPath`
+/// rather than looping forever.
+fn reconstruct_path(
+    came_from: &HashMap<(usize, usize), (usize, usize)>,
+    start: (usize, usize),
+    end: (usize, usize),
+) -> Result<Path, Error> {
+    let mut path = vec![end];
+    let mut seen = HashSet::from([end]);
+    let mut cell = end;
+    while cell != start {
+        cell = *came_from.get(&cell).ok_or(Error::CorruptPath)?;
+        if !seen.insert(cell) {
+            return Err(Error::CorruptPath);
+        }
+        path.push(cell);
+    }
+    path.reverse();
+    Ok(path)
+}
+
+fn bfs(
+    maze: &[Vec<char>],
+    start: (usize, usize),
+    expanded: &mut Expanded,
+    stats: &mut Stats,
+    is_passable: Passable,
+    goal: Goal,
+) -> Result<Option<Path>, Error> {
+    let mut frontier = VecDeque::from([start]);
+    stats.max_frontier_size = 1;
+    let mut came_from: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+    let mut visited: HashSet<(usize, usize)> = HashSet::from([start]);
+    while let Some((row, col)) = profile::time("bfs", "frontier", || frontier.pop_front()) {
+        if goal.reached((row, col)) {
+            return reconstruct_path(&came_from, start, (row, col)).map(Some);
+        }
+        expanded.push((row, col));
+
+        let next_cells = profile::time("bfs", "neighbours", || {
+            neighbours_in(maze, (row, col), goal.connectivity, is_passable)
+                .filter(|&next| visited.insert(next))
+                .collect::<Vec<_>>()
+        });
+        for next in next_cells {
+            came_from.insert(next, (row, col));
+            profile::time("bfs", "frontier", || frontier.push_back(next));
+        }
+        stats.max_frontier_size = stats.max_frontier_size.max(frontier.len());
+    }
+    Ok(None)
+}
+
+/// Whether moving in `EIGHT_DIRECTIONS[direction]` may enter `(row, col)`,
+/// which is only ever false for a one-way cell pointing elsewhere. One-way
+/// cells are never entered diagonally. Out of bounds cells are left for the
+/// caller to reject.
+fn can_enter(maze: &[Vec<char>], (row, col): (usize, usize), direction: usize) -> bool {
+    maze.get(row)
+        .and_then(|cells| cells.get(col))
+        .is_none_or(|&c| !ONE_WAY.contains(&c) || ONE_WAY.get(direction) == Some(&c))
+}
+
+/// Whether the step from `(row, col)` in `EIGHT_DIRECTIONS[direction]` is a
+/// diagonal one between two cells `is_passable` rejects (or that are out of
+/// bounds), squeezing through the corner where they touch.
+fn cuts_corner(
+    maze: &[Vec<char>],
+    (row, col): (usize, usize),
+    direction: usize,
+    is_passable: impl Fn((usize, usize)) -> bool,
+) -> bool {
+    let (dx, dy) = EIGHT_DIRECTIONS[direction];
+    let open = |(row, col): (usize, usize)| {
+        row < maze.len() && col < maze[row].len() && is_passable((row, col))
+    };
+    direction >= DIRECTIONS.len()
+        && !open((row.overflowing_add(dx).0, col))
+        && !open((row, col.overflowing_add(dy).0))
+}
+
+/// The in-bounds cells orthogonally adjacent to `(row, col)` that `is_passable`
+/// accepts and that one-way cells allow entering from here.
+fn neighbours_where<'a>(
+    maze: &'a [Vec<char>],
+    cell: (usize, usize),
+    is_passable: impl Fn((usize, usize)) -> bool + 'a,
+) -> impl Iterator<Item = (usize, usize)> + 'a {
+    neighbours_in(maze, cell, Connectivity::Four, is_passable)
+}
+
+/// Like `neighbours_where`, with the diagonal neighbours too when
+/// `connectivity` allows them.
+fn neighbours_in<'a>(
+    maze: &'a [Vec<char>],
+    (row, col): (usize, usize),
+    connectivity: Connectivity,
+    is_passable: impl Fn((usize, usize)) -> bool + 'a,
+) -> impl Iterator<Item = (usize, usize)> + 'a {
+    connectivity
+        .directions()
+        .iter()
+        .enumerate()
+        .map(move |(d, &(dx, dy))| (d, (row.overflowing_add(dx).0, col.overflowing_add(dy).0)))
+        .filter(move |&(d, next)| {
+            next.0 < maze.len()
+                && next.1 < maze[next.0].len()
+                && is_passable(next)
+                && can_enter(maze, next, d)
+                && !cuts_corner(maze, (row, col), d, &is_passable)
+        })
+        .map(|(_, cell)| cell)
+}
+
+/// The in-bounds, unblocked cells orthogonally adjacent to `(row, col)` that
+/// one-way cells allow entering from here.
+fn neighbours(
+    maze: &[Vec<char>],
+    cell: (usize, usize),
+) -> impl Iterator<Item = (usize, usize)> + '_ {
+    neighbours_where(maze, cell, move |(row, col)| !is_blocked(maze[row][col]))
+}
+
+/// Breadth first distance, in steps, from `from` to every cell reachable from it.
+fn distance_field(maze: &[Vec<char>], from: (usize, usize)) -> HashMap<(usize, usize), usize> {
+    let mut dist = HashMap::from([(from, 0)]);
+    let mut frontier = VecDeque::from([from]);
+    while let Some(cell) = frontier.pop_front() {
+        let d = dist[&cell];
+        for next in neighbours(maze, cell) {
+            if let Entry::Vacant(e) = dist.entry(next) {
+                e.insert(d + 1);
+                frontier.push_back(next);
+            }
+        }
+    }
+    dist
+}
+
+/// Labels every reachable open cell with its BFS distance from the start as a
+/// base-36 digit (wrapping past `'z'`), leaving the endpoints and path intact.
+/// Terrain cells are labelled too, so a digit next to the rings is never left
+/// to be mistaken for one of them. A maze without a start is left untouched.
+pub fn draw_rings(maze: &mut [Vec<char>]) {
+    let Some(start) = get_start(maze) else {
+        return;
+    };
+    for ((row, col), d) in distance_field(maze, start) {
+        if matches!(maze[row][col], ' ' | '@' | '1'..='9') {
+            maze[row][col] = std::char::from_digit((d % 36) as u32, 36).unwrap();
+        }
+    }
+}
+
+/// A shortest path from `start` to `end`, inclusive of both, without touching the grid.
+fn shortest_path(maze: &[Vec<char>], start: (usize, usize), end: (usize, usize)) -> Option<Path> {
+    let mut came_from = HashMap::new();
+    let mut frontier = VecDeque::from([start]);
+    while let Some(cell) = frontier.pop_front() {
+        if cell == end {
+            return reconstruct_path(&came_from, start, end).ok();
+        }
+        for next in neighbours(maze, cell) {
+            if next != start && !came_from.contains_key(&next) {
+                came_from.insert(next, cell);
+                frontier.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+/// Which of several equally short paths [`shortest_path_preferring`] picks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+    /// The fewest changes of direction.
+    Straight,
+    /// The most changes of direction.
+    Turns,
+    /// The lexicographically smallest sequence of `(row, col)` coordinates,
+    /// which favours going up, then left, as early as possible.
+    UpperLeft,
+    /// Staying as close as possible to the middle of the maze, measured as
+    /// the sum of squared distances of the path's cells from the centre.
+    Center,
+}
+
+/// The index into `DIRECTIONS` of the step from `from` to the adjacent `to`.
+fn direction(from: (usize, usize), to: (usize, usize)) -> usize {
+    DIRECTIONS
+        .iter()
+        .position(|&(dr, dc)| (from.0.wrapping_add(dr), from.1.wrapping_add(dc)) == to)
+        .expect("cells are adjacent")
+}
+
+/// The cells on some shortest path from `start` to `end` in order of their
+/// distance from the start, with each one's shortest path successors. Found
+/// by walking the BFS distance field from the start backwards from the end:
+/// a cell is on a shortest path if a successor one step further out is.
+fn shortest_path_edges(
+    maze: &[Vec<char>],
+    start: (usize, usize),
+    end: (usize, usize),
+) -> Option<(Path, Dag)> {
+    let dist = distance_field(maze, start);
+    dist.get(&end)?;
+    let mut by_distance = dist.keys().copied().collect::<Vec<_>>();
+    by_distance.sort_by_key(|cell| (dist[cell], *cell));
+
+    let mut dag = Dag::from([(end, Vec::new())]);
+    for &cell in by_distance.iter().rev() {
+        let successors = neighbours(maze, cell)
+            .filter(|next| dist.get(next) == Some(&(dist[&cell] + 1)) && dag.contains_key(next))
+            .collect::<Vec<_>>();
+        if !successors.is_empty() {
+            dag.insert(cell, successors);
+        }
+    }
+    by_distance.retain(|cell| dag.contains_key(cell));
+    Some((by_distance, dag))
+}
+
+/// Every edge that lies on a shortest path from the start to the end: each
+/// cell on one mapped to the cells it can step to next while staying on one,
+/// the end mapped to nothing. Where shortest routes split a cell has several
+/// successors, where they join a cell is the successor of several. Empty if
+/// the maze has no solution.
+pub fn shortest_path_dag(maze: &[Vec<char>]) -> Dag {
+    let (Some(start), Some(end)) = (get_start(maze), get_end(maze)) else {
+        return Dag::new();
+    };
+    shortest_path_edges(maze, start, end).map_or_else(Dag::new, |(_, dag)| dag)
+}
+
+/// A shortest path from the start to the end, inclusive of both, chosen among
+/// all the shortest ones by `prefer` by following [`shortest_path_dag`]:
+/// greedily for the upper-left policy, and for the others backwards from the
+/// end along the best predecessors.
+pub fn shortest_path_preferring(maze: &[Vec<char>], prefer: TieBreak) -> Option<Path> {
+    let (start, end) = (get_start(maze)?, get_end(maze)?);
+    let (by_distance, dag) = shortest_path_edges(maze, start, end)?;
+
+    if prefer == TieBreak::UpperLeft {
+        let mut path = vec![start];
+        while path[path.len() - 1] != end {
+            path.push(*dag[&path[path.len() - 1]].iter().min()?);
+        }
+        return Some(path);
+    }
+
+    // Best cost (turns, or distance from the centre) to reach each cell
+    // arriving in each direction, with the state it was reached from.
+    type State = ((usize, usize), Option<usize>);
+    let better = |cost: usize, best: usize| match prefer {
+        TieBreak::Turns => cost > best,
+        _ => cost < best,
+    };
+    let (rows, cols) = (maze.len(), maze[0].len());
+    let step_cost = |arrival: Option<usize>, (row, col): (usize, usize), d: usize| match prefer {
+        // Doubled coordinates keep the centre of even sized mazes integral.
+        TieBreak::Center => {
+            (2 * row).abs_diff(rows - 1).pow(2) + (2 * col).abs_diff(cols - 1).pow(2)
+        }
+        _ => usize::from(arrival.is_some_and(|a| a != d)),
+    };
+    let mut best: HashMap<State, (usize, Option<State>)> =
+        HashMap::from([((start, None), (0, None))]);
+    for &cell in &by_distance {
+        for arrival in [None, Some(0), Some(1), Some(2), Some(3)] {
+            let Some(&(cost, _)) = best.get(&(cell, arrival)) else {
+                continue;
+            };
+            for &next in &dag[&cell] {
+                let d = direction(cell, next);
+                let cost = cost + step_cost(arrival, next, d);
+                let state = (next, Some(d));
+                if best
+                    .get(&state)
+                    .is_none_or(|&(current, _)| better(cost, current))
+                {
+                    best.insert(state, (cost, Some((cell, arrival))));
+                }
+            }
+        }
+    }
+
+    let mut state = (0..4)
+        .map(|d| (end, Some(d)))
+        .filter(|state| best.contains_key(state))
+        .reduce(|a, b| match better(best[&b].0, best[&a].0) {
+            true => b,
+            false => a,
+        })
+        .unwrap_or((end, None));
+    let mut path = vec![end];
+    while let Some(&(_, Some(previous))) = best.get(&state) {
+        path.push(previous.0);
+        state = previous;
+    }
+    path.reverse();
+    Some(path)
+}
+
+/// The fewest walls that have to be knocked down to connect the start to the
+/// end, in the order the path crosses them. Searches over `(cell, breaks used)`
+/// states, where stepping onto a wall spends one break; states reached without
+/// a new break are explored first, so the first time the end is reached it is
+/// with the smallest number of breaks. Danger zones are never broken through.
+/// Returns `None` if the endpoints are missing or not even breaking walls
+/// connects them, and an empty list if the maze is already solvable.
+pub fn min_wall_breaks(maze: &[Vec<char>]) -> Option<Vec<(usize, usize)>> {
+    let (start, end) = (get_start(maze)?, get_end(maze)?);
+    let mut breaks = HashMap::from([(start, 0)]);
+    let mut came_from = HashMap::new();
+    let mut frontier = VecDeque::from([(start, 0)]);
+    while let Some((cell, used)) = frontier.pop_front() {
+        if cell == end {
+            let path = reconstruct_path(&came_from, start, end).ok()?;
+            return Some(
+                path.into_iter()
+                    .filter(|&(row, col)| maze[row][col] == WALL)
+                    .collect(),
+            );
+        }
+        if breaks[&cell] < used {
+            continue;
+        }
+        for next in neighbours_where(maze, cell, |(row, col)| maze[row][col] != DANGER) {
+            let wall = maze[next.0][next.1] == WALL;
+            let next_used = used + usize::from(wall);
+            if breaks.get(&next).is_some_and(|&best| best <= next_used) {
+                continue;
+            }
+            breaks.insert(next, next_used);
+            came_from.insert(next, cell);
+            match wall {
+                true => frontier.push_back((next, next_used)),
+                false => frontier.push_front((next, next_used)),
+            }
+        }
+    }
+    None
+}
+
+/// A form of the shortest solution that is identical for mazes related by any
+/// of the 8 grid symmetries (rotations and reflections). The path is written as
+/// moves (`U`, `R`, `D`, `L`), every symmetry is applied to that move string and
+/// the lexicographically smallest result is kept. Returns `None` if the maze has
+/// no solution. When several shortest paths exist, the one found depends on the
+/// neighbour order, so symmetric mazes are only guaranteed to match when the
+/// shortest path is unique.
+pub fn canonical_solution_signature(maze: &[Vec<char>]) -> Option<String> {
+    const MOVES: [char; 4] = ['U', 'R', 'D', 'L'];
+    let path = shortest_path(maze, get_start(maze)?, get_end(maze)?)?;
+    let moves = path
+        .windows(2)
+        .map(|w| {
+            match (
+                w[1].0 as isize - w[0].0 as isize,
+                w[1].1 as isize - w[0].1 as isize,
+            ) {
+                (-1, 0) => 0,
+                (0, 1) => 1,
+                (1, 0) => 2,
+                _ => 3,
+            }
+        })
+        .collect::<Vec<usize>>();
+
+    (0..4)
+        .flat_map(|k| [(k, false), (k, true)])
+        .map(|(k, mirrored)| {
+            moves
+                .iter()
+                .map(|&d| {
+                    MOVES[if mirrored {
+                        (k + 4 - d) % 4
+                    } else {
+                        (k + d) % 4
+                    }]
+                })
+                .collect::<String>()
+        })
+        .min()
+}
+
+fn manhattan_dist(p1: (usize, usize), p2: (usize, usize)) -> usize {
+    p1.0.abs_diff(p2.0) + p1.1.abs_diff(p2.1)
+}
+fn greedy_best_first_search(
+    maze: &[Vec<char>],
+    start: (usize, usize),
+    goal: Goal,
+    expanded: &mut Expanded,
+    stats: &mut Stats,
+    is_passable: Passable,
+) -> Result<Option<Path>, Error> {
+    // Among cells equally close to the goal the cheaper terrain goes first.
+    let mut frontier = BinaryHeap::from([(Reverse((goal.distance(start), 1)), start.0, start.1)]);
+    let mut came_from: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+    let mut visited: HashSet<(usize, usize)> = HashSet::new();
+    stats.max_frontier_size = 1;
+
+    while let Some((_, row, col)) =
+        profile::time("greedy_best_first_search", "frontier", || frontier.pop())
+    {
+        if goal.reached((row, col)) {
+            return reconstruct_path(&came_from, start, (row, col)).map(Some);
+        }
+        expanded.push((row, col));
+
+        let next_cells = profile::time("greedy_best_first_search", "neighbours", || {
+            neighbours_in(maze, (row, col), goal.connectivity, is_passable)
+                .filter(|&next| visited.insert(next))
+                .collect::<Vec<_>>()
+        });
+        for next in next_cells {
+            came_from.insert(next, (row, col));
+            let h = profile::time("greedy_best_first_search", "heuristic", || {
+                goal.distance(next)
+            });
+            profile::time("greedy_best_first_search", "frontier", || {
+                frontier.push((
+                    Reverse((h, cell_cost(maze[next.0][next.1]))),
+                    next.0,
+                    next.1,
+                ))
+            });
+        }
+        stats.max_frontier_size = stats.max_frontier_size.max(frontier.len());
+    }
+
+    Ok(None)
+}
+fn a_star(
+    maze: &[Vec<char>],
+    start: (usize, usize),
+    goal: Goal,
+    expanded: &mut Expanded,
+    stats: &mut Stats,
+    is_passable: Passable,
+) -> Result<Option<Path>, Error> {
+    // Entries carry the terrain cost `g` of getting there; the priority is
+    // `g` plus the heuristic, a lower bound since every step costs at least 1.
+    let mut frontier = BinaryHeap::from([(Reverse(goal.distance(start)), start.0, start.1, 0)]);
+    // The cheapest known cost of reaching each cell and the cell it was
+    // reached from. A cell is pushed again whenever a cheaper way to it turns
+    // up, and the stale entries are skipped when popped, so the first time the
+    // goal is popped its parents trace an optimal path.
+    let mut g_score = HashMap::from([(start, 0)]);
+    let mut came_from: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+    stats.max_frontier_size = 1;
+
+    while let Some((_, row, col, g)) = profile::time("a_star", "frontier", || frontier.pop()) {
+        if g_score[&(row, col)] < g {
+            continue;
+        }
+        if goal.reached((row, col)) {
+            return reconstruct_path(&came_from, start, (row, col)).map(Some);
+        }
+        expanded.push((row, col));
+
+        let next_cells = profile::time("a_star", "neighbours", || {
+            neighbours_in(maze, (row, col), goal.connectivity, is_passable)
+                .map(|next| (next, g + cell_cost(maze[next.0][next.1])))
+                .filter(|&(next, g)| g_score.get(&next).is_none_or(|&best| g < best))
+                .collect::<Vec<_>>()
+        });
+        for (next, g) in next_cells {
+            g_score.insert(next, g);
+            came_from.insert(next, (row, col));
+            let h = profile::time("a_star", "heuristic", || goal.distance(next));
+            profile::time("a_star", "frontier", || {
+                frontier.push((Reverse(g + h), next.0, next.1, g))
+            });
+        }
+        stats.max_frontier_size = stats.max_frontier_size.max(frontier.len());
+    }
+
+    Ok(None)
+}
+
+/// Solves `maze` in place, marking the path with `'*'` and, with
+/// `display_visited`, the expanded cells with `'@'`. When there is no path
+/// the grid is still marked before `Error::NoPathFound` is returned.
+pub fn maze_solver(
+    maze: &mut [Vec<char>],
+    algorithm: Algorithm,
+    display_visited: bool,
+) -> Result<(), Error> {
+    match maze_solver_within(maze, algorithm, display_visited, 0, Connectivity::Four)? {
+        (true, _) => Ok(()),
+        (false, _) => Err(Error::NoPathFound),
+    }
+}
+
+/// Like `maze_solver`, but the search moves to the neighbours `connectivity`
+/// allows and succeeds as soon as it enters any cell within `radius` steps of
+/// the end (manhattan distance with four neighbours, Chebyshev with eight).
+/// Greedy best first and A* aim for the nearest edge of that region with the
+/// matching distance, which keeps A* admissible. Also returns the statistics
+/// of the search.
+pub fn maze_solver_within(
+    maze: &mut [Vec<char>],
+    algorithm: Algorithm,
+    display_visited: bool,
+    radius: usize,
+    connectivity: Connectivity,
+) -> Result<(bool, Stats), Error> {
+    let grid: &[Vec<char>] = maze;
+    let goal = Goal {
+        end: get_end(grid).ok_or(Error::EndNotFound)?,
+        radius,
+        connectivity,
+    };
+    let search = search(
+        grid,
+        algorithm,
+        &|(row, col)| !is_blocked(grid[row][col]),
+        goal,
+    )?;
+    let solved = paint_search(maze, search.path, &search.expanded, display_visited);
+    Ok((solved, search.stats))
+}
+
+/// Like `maze_solver`, but routes through whichever cells `is_passable`
+/// accepts instead of reading walls from the grid. Out of bounds cells are
+/// never passed to the predicate. The grid is still used for the endpoints
+/// and is marked with the search result as usual. Returns whether the end
+/// was reached.
+pub fn maze_solver_with(
+    maze: &mut [Vec<char>],
+    algorithm: Algorithm,
+    display_visited: bool,
+    is_passable: Passable,
+) -> Result<bool, Error> {
+    let end = get_end(maze).ok_or(Error::EndNotFound)?;
+    let goal = Goal {
+        end,
+        radius: 0,
+        connectivity: Connectivity::Four,
+    };
+    let search = search(maze, algorithm, is_passable, goal)?;
+    Ok(paint_search(
+        maze,
+        search.path,
+        &search.expanded,
+        display_visited,
+    ))
+}
+
+/// Marks the expanded cells with `'@'` when `display_visited` is set and the
+/// path with `'*'` over them, returning whether there was a path.
+fn paint_search(
+    maze: &mut [Vec<char>],
+    path: Option<Path>,
+    expanded: &[(usize, usize)],
+    display_visited: bool,
+) -> bool {
+    if display_visited {
+        for &(row, col) in expanded {
+            if maze[row][col] != START {
+                maze[row][col] = '@';
+            }
+        }
+    }
+    let Some(path) = path else {
+        return false;
+    };
+    for &cell in &path[1..] {
+        mark_path_cell(maze, cell);
+    }
+    true
+}
+
+/// What the solvers measure about their own search, for comparing them.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    /// Cells expanded; for DFS every cell the search entered.
+    pub nodes_visited: usize,
+    /// Steps from start to end, 0 if no path was found.
+    pub path_length: usize,
+    /// The most cells waiting in the frontier at once; for DFS the deepest
+    /// the chain of cells being explored got.
+    pub max_frontier_size: usize,
+}
+
+/// The result of [`search`]: the path (both ends included) if there is one,
+/// every cell expanded on the way in the order they were expanded, and the
+/// statistics of the run.
+struct Search {
+    path: Option<Path>,
+    expanded: Expanded,
+    stats: Stats,
+}
+
+/// Runs `algorithm` from the start towards `goal`.
+fn search(
+    maze: &[Vec<char>],
+    algorithm: Algorithm,
+    is_passable: Passable,
+    goal: Goal,
+) -> Result<Search, Error> {
+    let start = get_start(maze).ok_or(Error::StartNotFound)?;
+    let mut expanded = Vec::new();
+    let mut stats = Stats::default();
+    // The end right next to the start is a one step path for every
+    // algorithm, no search needed. A start already inside the goal region
+    // is left to the searches, which all stop on it with a zero step path.
+    if !goal.reached(start)
+        && neighbours_in(maze, start, goal.connectivity, is_passable).any(|cell| cell == goal.end)
+    {
+        stats.path_length = 1;
+        let path = Some(vec![start, goal.end]);
+        return Ok(Search {
+            path,
+            expanded,
+            stats,
+        });
+    }
+
+    let path = match algorithm {
+        Algorithm::DFS => {
+            let mut state = DfsState::default();
+            let found = dfs(maze, start, &mut state, is_passable, goal);
+            // The chain being explored is the frontier of a depth first search.
+            stats.max_frontier_size = state.max_depth;
+            stats.nodes_visited = state.vis.len();
+            expanded = state.expanded;
+            found.then_some(state.path)
+        }
+        Algorithm::BFS => bfs(maze, start, &mut expanded, &mut stats, is_passable, goal)?,
+        Algorithm::GreedyBestFirst => {
+            greedy_best_first_search(maze, start, goal, &mut expanded, &mut stats, is_passable)?
+        }
+        Algorithm::AStar => a_star(maze, start, goal, &mut expanded, &mut stats, is_passable)?,
+    };
+    if algorithm != Algorithm::DFS {
+        stats.nodes_visited = expanded.len();
+    }
+    stats.path_length = path.as_ref().map_or(0, |path| path.len() - 1);
+    Ok(Search {
+        path,
+        expanded,
+        stats,
+    })
+}
+
+/// A solved maze: the cells from `start` to `end`, both included, in the
+/// order the path walks them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    pub path: Path,
+    pub start: (usize, usize),
+    pub end: (usize, usize),
+}
+
+/// Solves `maze` with `algorithm` without touching it, returning the path
+/// the CLI marks with `'*'`, or `None` if the end cannot be reached.
+pub fn solve(maze: &[Vec<char>], algorithm: Algorithm) -> Result<Option<Solution>, Error> {
+    is_maze_valid(maze)?;
+    let (start, end) = (get_start(maze).unwrap(), get_end(maze).unwrap());
+    let goal = Goal {
+        end,
+        radius: 0,
+        connectivity: Connectivity::Four,
+    };
+    let is_passable = |(row, col): (usize, usize)| !is_blocked(maze[row][col]);
+    let path = search(maze, algorithm, &is_passable, goal)?.path;
+    Ok(path.map(|path| Solution { path, start, end }))
+}
+
+/// The path length and cost each algorithm finds on one maze, and whether
+/// the optimal algorithms lived up to their promise.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Audit {
+    /// Steps from start to end per algorithm, `None` where it found no path.
+    pub lengths: Vec<(Algorithm, Option<usize>)>,
+    /// The terrain cost of the same paths (see [`cell_cost`]), equal to the
+    /// step count on mazes without terrain.
+    pub costs: Vec<(Algorithm, Option<usize>)>,
+    pub passed: bool,
+}
+
+/// Runs every algorithm on `maze` and checks that the ones that promise
+/// optimal paths are optimal by their own measure: BFS takes no more steps
+/// and A* pays no more terrain cost than any algorithm, and all of them agree
+/// on whether there is a path. Without terrain the measures coincide, so BFS
+/// and A* must report the same length. DFS and greedy best first are only
+/// compared against; they may legitimately be worse. The searches move to
+/// the neighbours `connectivity` allows.
+pub fn audit(maze: &[Vec<char>], connectivity: Connectivity) -> Result<Audit, Error> {
+    is_maze_valid(maze)?;
+    let goal = Goal {
+        end: get_end(maze).unwrap(),
+        radius: 0,
+        connectivity,
+    };
+    let is_passable = |(row, col): (usize, usize)| !is_blocked(maze[row][col]);
+    let paths = Algorithm::ALL
+        .into_iter()
+        .map(|algorithm| Ok((algorithm, search(maze, algorithm, &is_passable, goal)?.path)))
+        .collect::<Result<Vec<_>, Error>>()?;
+    let measure = |f: &dyn Fn(&Path) -> usize| {
+        paths
+            .iter()
+            .map(|(algorithm, path)| (*algorithm, path.as_ref().map(f)))
+            .collect::<Vec<_>>()
+    };
+    let lengths = measure(&|path| path.len() - 1);
+    let costs = measure(&|path| {
+        path[1..]
+            .iter()
+            .map(|&(row, col)| cell_cost(maze[row][col]))
+            .sum()
+    });
+    let passed = [(Algorithm::BFS, &lengths), (Algorithm::AStar, &costs)]
+        .into_iter()
+        .all(|(optimal, measured)| {
+            let own = measured.iter().find(|(a, _)| *a == optimal).unwrap().1;
+            measured
+                .iter()
+                .all(|&(_, other)| other.is_some() == own.is_some() && own <= other)
+        });
+    Ok(Audit {
+        lengths,
+        costs,
+        passed,
+    })
+}
+
+/// Runs `algorithm` without touching `maze`, returning the outcome, the path
+/// from start to end (inclusive) if one was found, and every cell the search
+/// touched: each expanded cell plus the path itself. Rendering is left
+/// entirely to the caller.
+pub fn solve_full(
+    maze: &[Vec<char>],
+    algorithm: Algorithm,
+) -> Result<(Outcome, Option<Path>, Visited), Error> {
+    solve_full_within(maze, algorithm, 0, Connectivity::Four)
+}
+
+/// Like `solve_full`, with the goal region and neighbours of
+/// `maze_solver_within`.
+pub fn solve_full_within(
+    maze: &[Vec<char>],
+    algorithm: Algorithm,
+    radius: usize,
+    connectivity: Connectivity,
+) -> Result<(Outcome, Option<Path>, Visited), Error> {
+    is_maze_valid(maze)?;
+    let goal = Goal {
+        end: get_end(maze).unwrap(),
+        radius,
+        connectivity,
+    };
+    let is_passable = |(row, col): (usize, usize)| !is_blocked(maze[row][col]);
+    let Search { path, expanded, .. } = search(maze, algorithm, &is_passable, goal)?;
+    let mut visited = expanded.into_iter().collect::<Visited>();
+    visited.extend(path.iter().flatten().copied());
+    let outcome = match path {
+        Some(_) => Outcome::Solved,
+        None => Outcome::Unsolvable,
+    };
+    Ok((outcome, path, visited))
+}
+
+fn run_searcher(
+    mut searcher: searcher::Searcher,
+) -> Result<(Outcome, Option<Path>, Visited), Error> {
+    let mut visited = HashSet::new();
+    loop {
+        match searcher.step() {
+            searcher::Step::Expanded(cell) => _ = visited.insert(cell),
+            searcher::Step::Found(path) => {
+                visited.extend(path.iter().copied());
+                return Ok((Outcome::Solved, Some(path), visited));
+            }
+            searcher::Step::Exhausted => return Ok((Outcome::Unsolvable, None, visited)),
+            searcher::Step::Failed(error) => return Err(error),
+        }
+    }
+}
+
+/// Why a search never expanded a cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PruneReason {
+    Wall,
+    Danger,
+    /// Open, but there is no way to it from the start.
+    OutOfComponent,
+    /// Reachable, but the search stopped at the end before getting to it.
+    NotReached,
+}
+
+impl PruneReason {
+    /// The character `--diagnose` draws the cell with.
+    pub fn glyph(self) -> char {
+        match self {
+            PruneReason::Wall => WALL,
+            PruneReason::Danger => DANGER,
+            PruneReason::OutOfComponent => 'x',
+            PruneReason::NotReached => '.',
+        }
+    }
+}
+
+/// Runs `algorithm` like [`solve_full`] and explains every cell it did not
+/// touch. Cells that were expanded or lie on the path have no entry.
+pub fn diagnose(
+    maze: &[Vec<char>],
+    algorithm: Algorithm,
+) -> Result<(Option<Path>, Visited, Diagnosis), Error> {
+    let (_, path, visited) = solve_full(maze, algorithm)?;
+    let reachable = distance_field(maze, get_start(maze).unwrap());
+    let reasons = maze
+        .iter()
+        .enumerate()
+        .flat_map(|(row, cells)| {
+            cells
+                .iter()
+                .enumerate()
+                .map(move |(col, &c)| ((row, col), c))
+        })
+        .filter(|(cell, _)| !visited.contains(cell))
+        .map(|(cell, c)| {
+            let reason = match c {
+                WALL => PruneReason::Wall,
+                DANGER => PruneReason::Danger,
+                _ if reachable.contains_key(&cell) => PruneReason::NotReached,
+                _ => PruneReason::OutOfComponent,
+            };
+            (cell, reason)
+        })
+        .collect();
+    Ok((path, visited, reasons))
+}
+
+/// Solves `maze` from A to B and again with the endpoints swapped, returning
+/// both path lengths in steps (`None` where no path was found). In a maze
+/// without one-way cells the two must agree for the optimal algorithms (BFS
+/// and A*), so a mismatch points at a solver bug or a directional passage.
+/// DFS and greedy best first give no such guarantee and are rejected with
+/// `Error::InvalidArgument`.
+pub fn both_directions(
+    maze: &[Vec<char>],
+    algorithm: Algorithm,
+) -> Result<(Option<usize>, Option<usize>), Error> {
+    if !algorithm.is_optimal() {
+        return Err(Error::InvalidArgument);
+    }
+    let reversed = maze
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|&c| match c {
+                    START => END,
+                    END => START,
+                    c => c,
+                })
+                .collect()
+        })
+        .collect::<Vec<Vec<char>>>();
+    let length = |maze: &[Vec<char>]| -> Result<Option<usize>, Error> {
+        Ok(solve_full(maze, algorithm)?.1.map(|path| path.len() - 1))
+    };
+    Ok((length(maze)?, length(&reversed)?))
+}
+
+/// Solves `maze` `runs` times and returns the first run (counting from 0)
+/// whose outcome, path or visited set differs from run 0, or `None` if every
+/// run agreed. With `random_ties` each run steps a [`searcher::Searcher`]
+/// that breaks frontier ties with a fresh generator from `seeds`, which is
+/// expected to be flagged on mazes with ties unless `seeds` was given a seed.
+pub fn verify_determinism(
+    maze: &[Vec<char>],
+    algorithm: Algorithm,
+    runs: usize,
+    random_ties: bool,
+    seeds: rng::Seeds,
+) -> Result<Option<usize>, Error> {
+    is_maze_valid(maze)?;
+    let start = get_start(maze).unwrap();
+    let end = get_end(maze).unwrap();
+    let run = || match random_ties {
+        true => run_searcher(
+            searcher::Searcher::new(maze, algorithm, start, end)
+                .random_ties(seeds.rng("random-ties")),
+        ),
+        false => solve_full(maze, algorithm),
+    };
+    let first = run()?;
+    for i in 1..runs {
+        if run()? != first {
+            return Ok(Some(i));
+        }
+    }
+    Ok(None)
+}
+
+/// Whether the end can be reached from the start in exactly `k` steps when
+/// cells, the endpoints included, may be revisited any number of times. The
+/// walk just has to be standing on the end after its `k`th step.
+///
+/// Each layer of cells after `i` steps only depends on the one before, so the
+/// layers eventually repeat. On a grid without one-way cells they settle into
+/// a period of 2 (the grid is bipartite, and a walk can always step back and
+/// forth); one-way loops can give longer periods. The period is found with
+/// Brent's cycle detection, and once it is known the remaining steps are
+/// skipped, so huge `k` cost no more than the number of distinct layers.
+pub fn reachable_in_exactly(maze: &[Vec<char>], k: usize) -> bool {
+    let (Some(start), Some(end)) = (get_start(maze), get_end(maze)) else {
+        return false;
+    };
+    let advance = |layer: &HashSet<(usize, usize)>| -> HashSet<(usize, usize)> {
+        layer
+            .iter()
+            .flat_map(|&cell| neighbours(maze, cell))
+            .collect()
+    };
+    // `saved` is the layer after `steps - period` steps; the layers repeat
+    // with `period` once `layer` equals it.
+    let mut saved = HashSet::from([start]);
+    let mut layer = saved.clone();
+    let (mut power, mut period) = (1, 0);
+    let mut steps = 0;
+    while steps < k {
+        layer = advance(&layer);
+        steps += 1;
+        period += 1;
+        if layer.is_empty() {
+            return false;
+        }
+        if layer == saved {
+            for _ in 0..(k - steps) % period {
+                layer = advance(&layer);
+            }
+            break;
+        }
+        if period == power {
+            saved = layer.clone();
+            power *= 2;
+            period = 0;
+        }
+    }
+    layer.contains(&end)
+}
+
+/// Upscales `maze` so each cell becomes an `n` x `n` block of the same kind,
+/// which preserves connectivity. The start and end occupy only the top-left
+/// cell of their blocks, the rest of those blocks is open, so a path of
+/// length `l` in the original has length `l * n` in the scaled maze.
+pub fn scale_maze(maze: &[Vec<char>], n: usize) -> Vec<Vec<char>> {
+    let block = |c: char, first: bool| match c {
+        START | END if !first => ' ',
+        c => c,
+    };
+    maze.iter()
+        .flat_map(|row| {
+            (0..n).map(move |dr| {
+                row.iter()
+                    .flat_map(|&c| (0..n).map(move |dc| block(c, dr == 0 && dc == 0)))
+                    .collect()
+            })
+        })
+        .collect()
+}
+
+/// Shortens `path` until no cell can be bypassed: whenever a later cell of the
+/// path is adjacent to (or the same as) an earlier one, everything in between
+/// is cut out. The result is still a valid path between the same endpoints
+/// but is only locally minimal, it is not necessarily a shortest path.
+/// Adjacency follows `connectivity`, which should be what found the path.
+pub fn locally_minimize_path(
+    maze: &[Vec<char>],
+    path: &[(usize, usize)],
+    connectivity: Connectivity,
+) -> Path {
+    let is_passable = |(row, col): (usize, usize)| !is_blocked(maze[row][col]);
+    let mut path = path.to_vec();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        let mut i = 0;
+        while i + 2 < path.len() {
+            let cell = path[i];
+            let shortcut = (i + 2..path.len()).rev().find(|&j| {
+                path[j] == cell
+                    || neighbours_in(maze, cell, connectivity, is_passable)
+                        .any(|next| next == path[j])
+            });
+            if let Some(j) = shortcut {
+                let from = if path[j] == cell { i } else { i + 1 };
+                path.drain(from..j);
+                changed = true;
+            }
+            i += 1;
+        }
+    }
+    path
+}
+
+/// Marks the cells of `path` with `'*'`, except for the start and the end.
+/// A path that stops short of the end is marked up to its last cell.
+pub fn paint_path(maze: &mut [Vec<char>], path: &[(usize, usize)]) {
+    for &cell in path {
+        mark_path_cell(maze, cell);
+    }
+}
+
+/// A grid the size of `maze` with `'1'` on every cell of `path`, endpoints
+/// included, and `'0'` everywhere else. A solved path of `path_length` steps
+/// therefore has `path_length + 1` ones.
+pub fn path_mask(maze: &[Vec<char>], path: &[(usize, usize)]) -> Vec<Vec<char>> {
+    let mut mask = maze
+        .iter()
+        .map(|row| vec!['0'; row.len()])
+        .collect::<Vec<_>>();
+    for &(row, col) in path {
+        mask[row][col] = '1';
+    }
+    mask
+}
+
+/// Rewrites a solved grid into its canonical form: walls, open cells and the
+/// endpoints as they were in `original`, the path as `'*'` and visited cells
+/// as `'@'` only when `display_visited` is set. Every algorithm leaves the grid
+/// in a slightly different state, this makes their outputs directly diffable.
+pub fn normalize_output(original: &[Vec<char>], solved: &mut [Vec<char>], display_visited: bool) {
+    for (orig_row, row) in original.iter().zip(solved.iter_mut()) {
+        for (&orig, cell) in orig_row.iter().zip(row.iter_mut()) {
+            *cell = match (orig, *cell) {
+                (START | END | DANGER | WALL, _) => orig,
+                (_, '*') => '*',
+                (_, '@') if display_visited => '@',
+                _ => orig,
+            };
+        }
+    }
+}
+
+/// The characters an input file uses for the start, the end and walls.
+/// Parsing translates them to `START`, `END` and `WALL`, so the solvers never
+/// see the chosen characters; `'█'` is always read as a wall as well.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Charset {
+    pub start: char,
+    pub end: char,
+    pub wall: char,
+}
+
+impl Default for Charset {
+    fn default() -> Self {
+        Self {
+            start: START,
+            end: END,
+            wall: '#',
+        }
+    }
+}
+
+impl Charset {
+    /// Fails with `Error::ConflictingCharacter` if two roles share a character
+    /// or one of them already has a meaning of its own (open, danger,
+    /// one-way, terrain or a solver mark).
+    pub fn validate(self) -> Result<(), Error> {
+        let taken = |c: char| {
+            RESERVED_CHARS.contains(c) || (c != START && c != END && VALID_CHARS.contains(c))
+        };
+        if self.start == self.end || self.start == self.wall {
+            Err(Error::ConflictingCharacter(self.start))
+        } else if self.end == self.wall {
+            Err(Error::ConflictingCharacter(self.end))
+        } else if let Some(c) = [self.start, self.end]
+            .into_iter()
+            .chain((self.wall != WALL).then_some(self.wall))
+            .find(|&c| taken(c))
+        {
+            Err(Error::ConflictingCharacter(c))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn translate(self, c: char) -> char {
+        match c {
+            c if c == self.start => START,
+            c if c == self.end => END,
+            c if c == self.wall || c == WALL => WALL,
+            // The default glyphs mean nothing once they have been replaced;
+            // map them to a character validation rejects.
+            START | END | '#' => char::REPLACEMENT_CHARACTER,
+            c => c,
+        }
+    }
+}
+
+pub fn parse_maze(contents: &str) -> Vec<Vec<char>> {
+    parse_maze_in(contents, Charset::default())
+}
+
+/// Parses `contents` written with `charset`'s start, end and wall characters.
+pub fn parse_maze_in(contents: &str, charset: Charset) -> Vec<Vec<char>> {
+    contents
+        .lines()
+        .map(|line| line.chars().map(|c| charset.translate(c)).collect())
+        .collect()
+}
+
+pub fn read_maze(path: &str) -> Result<Vec<Vec<char>>, Error> {
+    let file = std::fs::File::open(path).map_err(|_| Error::FileNotFound)?;
+    read_maze_from(file, Charset::default())
+}
+
+/// Reads and validates a whole maze from `reader` (a file, stdin, ...)
+/// written in `charset`.
+pub fn read_maze_from(mut reader: impl Read, charset: Charset) -> Result<Vec<Vec<char>>, Error> {
+    charset.validate()?;
+    let mut contents = String::new();
+    let _ = reader.read_to_string(&mut contents);
+    let grid = parse_maze_in(&contents, charset);
+    is_maze_valid(&grid)?;
+    Ok(grid)
+}
+
+pub fn parse_algorithm(name: &str) -> Result<Algorithm, Error> {
+    match name {
+        "A*" => Ok(Algorithm::AStar),
+        "BFS" => Ok(Algorithm::BFS),
+        "DFS" => Ok(Algorithm::DFS),
+        "GBFS" => Ok(Algorithm::GreedyBestFirst),
+        _ => Err(Error::InvalidAlgorithm),
+    }
+}
+
+/// Writes `grid` to `out` one row at a time, producing the same bytes as
+/// `render` without building the whole string first. Pair it with a
+/// `BufWriter` for large grids.
+pub fn write_grid(grid: &[Vec<char>], out: &mut impl std::io::Write) -> std::io::Result<()> {
+    let mut line = String::new();
+    for row in grid {
+        line.clear();
+        line.extend(row.iter());
+        line.push('\n');
+        out.write_all(line.as_bytes())?;
+    }
+    Ok(())
+}
+
+pub fn render(grid: &[Vec<char>]) -> String {
+    grid.iter()
+        .map(|row| row.iter().collect::<String>() + "\n")
+        .collect::<String>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses an indented maze literal, one row per non-empty line.
+    fn maze(rows: &str) -> Vec<Vec<char>> {
+        let rows = rows
+            .lines()
+            .map(str::trim_start)
+            .filter(|row| !row.is_empty());
+        parse_maze(&rows.collect::<Vec<_>>().join("\n"))
+    }
+
+    #[test]
+    fn normalized_dfs_and_bfs_agree_on_a_unique_path() {
+        let original = maze(
+            "
+            #######
+            #A#   #
+            # # # #
+            #   #B#
+            #######
+            ",
+        );
+        let solved = |algorithm| {
+            let mut grid = original.clone();
+            maze_solver(&mut grid, algorithm, true).unwrap();
+            normalize_output(&original, &mut grid, false);
+            grid
+        };
+        assert_eq!(solved(Algorithm::DFS), solved(Algorithm::BFS));
+    }
+
+    #[test]
+    fn every_valid_char_is_accepted() {
+        for c in valid_chars().chars() {
+            let grid = vec![vec![START, c, END]];
+            assert_eq!(is_maze_valid(&grid), Ok(()), "{c:?}");
+        }
+    }
+
+    #[test]
+    fn rings_count_steps_from_the_start() {
+        let mut grid = maze(
+            "
+            ######
+            #A  ##
+            ### ##
+            #B   #
+            ######
+            ",
+        );
+        draw_rings(&mut grid);
+        let expected = maze(
+            "
+            ######
+            #A12##
+            ###3##
+            #B545#
+            ######
+            ",
+        );
+        assert_eq!(grid, expected);
+
+        let mut no_start = maze("# B#");
+        draw_rings(&mut no_start);
+        assert_eq!(no_start, maze("# B#"));
+    }
+
+    #[test]
+    fn cyclic_parents_are_a_corrupt_path() {
+        let came_from = HashMap::from([((0, 3), (0, 2)), ((0, 2), (0, 1)), ((0, 1), (0, 2))]);
+        assert_eq!(
+            reconstruct_path(&came_from, (0, 0), (0, 3)),
+            Err(Error::CorruptPath)
+        );
+    }
+
+    #[test]
+    fn signature_ignores_rotation() {
+        let grid = maze(
+            "
+            ######
+            #A## #
+            #  # #
+            ##   #
+            ###B##
+            ",
+        );
+        let rotated = symmetry::Symmetry::Rotate90.apply_to_maze(&grid);
+        assert_ne!(rotated, grid);
+        let signature = canonical_solution_signature(&grid);
+        assert!(signature.is_some());
+        assert_eq!(canonical_solution_signature(&rotated), signature);
+    }
+
+    #[test]
+    fn solver_marks_in_input_are_reserved() {
+        for c in ['@', '*'] {
+            let contents = format!("#####\n#A{c}B#\n#####");
+            assert_eq!(
+                read_maze_from(contents.as_bytes(), Charset::default()),
+                Err(Error::ReservedCharacter(c))
+            );
+        }
+    }
+
+    #[test]
+    fn passability_predicate_keeps_the_path_off_blocked_cells() {
+        let grid = maze(
+            "
+            #######
+            #A   B#
+            #     #
+            #     #
+            #######
+            ",
+        );
+        // Even columns can only be crossed along the bottom row.
+        let blocked = |(row, col): (usize, usize)| col % 2 == 0 && row != 3;
+        let passable =
+            |(row, col): (usize, usize)| !is_blocked(grid[row][col]) && !blocked((row, col));
+        for algorithm in Algorithm::ALL {
+            let mut solved = grid.clone();
+            assert!(maze_solver_with(&mut solved, algorithm, false, &passable).unwrap());
+            for (row, cells) in solved.iter().enumerate() {
+                for (col, &c) in cells.iter().enumerate() {
+                    assert!(c != '*' || !blocked((row, col)), "{algorithm:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn full_solve_visits_every_path_cell() {
+        let grid = maze(
+            "
+            #######
+            #A  # #
+            # #   #
+            #   #B#
+            #######
+            ",
+        );
+        for algorithm in Algorithm::ALL {
+            let (outcome, path, visited) = solve_full(&grid, algorithm).unwrap();
+            assert_eq!(outcome, Outcome::Solved);
+            let path = path.unwrap();
+            assert_eq!(path.first(), get_start(&grid).as_ref());
+            assert_eq!(path.last(), get_end(&grid).as_ref());
+            assert!(
+                path.iter().all(|cell| visited.contains(cell)),
+                "{algorithm:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn trimming_removes_dfs_jogs() {
+        let grid = maze(
+            "
+            #######
+            #A    #
+            #     #
+            #    B#
+            #######
+            ",
+        );
+        let (_, path, _) = solve_full(&grid, Algorithm::DFS).unwrap();
+        let path = path.unwrap();
+        let trimmed = locally_minimize_path(&grid, &path, Connectivity::Four);
+        assert!(trimmed.len() < path.len());
+        assert_eq!(
+            (trimmed.first(), trimmed.last()),
+            (path.first(), path.last())
+        );
+        for step in trimmed.windows(2) {
+            assert_eq!(manhattan_dist(step[0], step[1]), 1);
+            assert!(!is_blocked(grid[step[1].0][step[1].1]));
+        }
+    }
+
+    #[test]
+    fn random_ties_break_determinism_unless_seeded() {
+        let grid = maze(
+            "
+            ########
+            #A     #
+            #      #
+            #      #
+            #     B#
+            ########
+            ",
+        );
+        let verify = |random_ties, seed| {
+            verify_determinism(
+                &grid,
+                Algorithm::DFS,
+                20,
+                random_ties,
+                rng::Seeds::new(seed),
+            )
+            .unwrap()
+        };
+        assert_eq!(verify(false, None), None);
+        assert!(verify(true, None).is_some());
+        assert_eq!(verify(true, Some(7)), None);
+    }
+
+    #[test]
+    fn scaling_multiplies_the_path_length() {
+        let grid = maze(
+            "
+            A #
+            # #
+            #B#
+            ",
+        );
+        let length =
+            |grid: &[Vec<char>]| solve(grid, Algorithm::BFS).unwrap().unwrap().path.len() - 1;
+        assert_eq!(length(&grid), 3);
+        let scaled = scale_maze(&grid, 2);
+        assert_eq!((scaled.len(), scaled[0].len()), (6, 6));
+        assert_eq!(length(&scaled), 6);
+    }
+
+    #[test]
+    fn exact_steps_follow_parity() {
+        let grid = maze(
+            "
+            #######
+            #A   B#
+            #######
+            ",
+        );
+        assert!(!reachable_in_exactly(&grid, 3));
+        assert!(reachable_in_exactly(&grid, 4));
+        assert!(!reachable_in_exactly(&grid, 5));
+        assert!(reachable_in_exactly(&grid, 6));
+        assert!(reachable_in_exactly(&grid, 1 << 40));
+    }
+
+    #[test]
+    fn set_cell_validates_each_edit() {
+        let mut grid = maze(
+            "
+            #####
+            #A B#
+            #####
+            ",
+        );
+        assert_eq!(set_cell(&mut grid, 1, 2, '#'), Ok(()));
+        assert_eq!(grid[1][2], WALL);
+        assert_eq!(set_cell(&mut grid, 1, 2, ' '), Ok(()));
+        assert_eq!(grid[1][2], ' ');
+
+        let before = grid.clone();
+        assert_eq!(set_cell(&mut grid, 1, 2, START), Err(Error::DuplicateStart));
+        assert_eq!(set_cell(&mut grid, 1, 2, 'x'), Err(Error::InvalidCharacter));
+        assert_eq!(
+            set_cell(&mut grid, 1, 2, '@'),
+            Err(Error::ReservedCharacter('@'))
+        );
+        assert_eq!(set_cell(&mut grid, 1, 1, ' '), Err(Error::StartNotFound));
+        assert_eq!(set_cell(&mut grid, 5, 0, ' '), Err(Error::OutOfBounds));
+        assert_eq!(grid, before);
+    }
+
+    #[test]
+    fn one_way_cells_make_directions_differ() {
+        let ordinary = maze(
+            "
+            ######
+            #A # #
+            #   B#
+            ######
+            ",
+        );
+        assert_eq!(
+            both_directions(&ordinary, Algorithm::BFS),
+            Ok((Some(4), Some(4)))
+        );
+
+        let one_way = maze(
+            "
+            #######
+            #A > B#
+            # ### #
+            #     #
+            #######
+            ",
+        );
+        assert_eq!(
+            both_directions(&one_way, Algorithm::BFS),
+            Ok((Some(4), Some(8)))
+        );
+
+        for algorithm in [Algorithm::DFS, Algorithm::GreedyBestFirst] {
+            assert_eq!(
+                both_directions(&ordinary, algorithm),
+                Err(Error::InvalidArgument)
+            );
+        }
+    }
+
+    #[test]
+    fn write_grid_matches_render() {
+        let mut grid = maze(
+            "
+            ######
+            #A 1 #
+            #  #B#
+            ######
+            ",
+        );
+        maze_solver(&mut grid, Algorithm::BFS, true).unwrap();
+        let mut out = Vec::new();
+        write_grid(&grid, &mut out).unwrap();
+        assert_eq!(out, render(&grid).into_bytes());
+    }
+
+    #[test]
+    fn paths_route_around_danger() {
+        let detour = maze(
+            "
+            #######
+            #A D B#
+            #     #
+            #######
+            ",
+        );
+        for algorithm in Algorithm::ALL {
+            let solution = solve(&detour, algorithm).unwrap().unwrap();
+            assert!(solution
+                .path
+                .iter()
+                .all(|&(row, col)| detour[row][col] != DANGER));
+        }
+
+        let blocked = maze(
+            "
+            #######
+            #A D B#
+            #######
+            ",
+        );
+        for algorithm in Algorithm::ALL {
+            assert_eq!(solve(&blocked, algorithm), Ok(None));
+        }
+    }
+
+    #[test]
+    fn audit_passes_on_a_maze_with_loops_and_terrain() {
+        let grid = maze(
+            "
+            #########
+            #A  #   #
+            # # # # #
+            # #  9# #
+            # ### # #
+            #   #  B#
+            #########
+            ",
+        );
+        let audit = audit(&grid, Connectivity::Four).unwrap();
+        assert!(audit.passed);
+        assert_eq!(audit.lengths.len(), Algorithm::ALL.len());
+        assert!(audit.lengths.iter().all(|(_, length)| length.is_some()));
+    }
+
+    #[test]
+    fn goal_radius_stops_short_of_the_end() {
+        let grid = maze(
+            "
+            ##########
+            #A      B#
+            ##########
+            ",
+        );
+        let end = get_end(&grid).unwrap();
+        for algorithm in Algorithm::ALL {
+            let full = solve_full(&grid, algorithm).unwrap().1.unwrap();
+            let (_, path, _) = solve_full_within(&grid, algorithm, 2, Connectivity::Four).unwrap();
+            let path = path.unwrap();
+            assert_eq!(manhattan_dist(*path.last().unwrap(), end), 2);
+            assert_eq!(path.len() + 2, full.len());
+
+            let (_, path, _) = solve_full_within(&grid, algorithm, 7, Connectivity::Four).unwrap();
+            assert_eq!(path, Some(vec![get_start(&grid).unwrap()]), "{algorithm:?}");
+        }
+    }
+
+    #[test]
+    fn one_wall_break_connects_the_endpoints() {
+        let grid = maze(
+            "
+            #######
+            #A ## #
+            #   #B#
+            #######
+            ",
+        );
+        assert_eq!(min_wall_breaks(&grid), Some(vec![(2, 4)]));
+        assert_eq!(min_wall_breaks(&maze("#A B#")), Some(vec![]));
+    }
+
+    #[test]
+    fn path_mask_marks_exactly_the_path() {
+        let grid = maze(
+            "
+            ######
+            #A # #
+            #    #
+            ### B#
+            ######
+            ",
+        );
+        let path = solve(&grid, Algorithm::BFS).unwrap().unwrap().path;
+        let mask = path_mask(&grid, &path);
+        let ones = (0..grid.len())
+            .flat_map(|row| (0..grid[row].len()).map(move |col| (row, col)))
+            .filter(|&(row, col)| mask[row][col] == '1')
+            .collect::<HashSet<_>>();
+        assert_eq!(ones, path.iter().copied().collect());
+        assert!(mask.iter().flatten().all(|&c| c == '0' || c == '1'));
+
+        let (_, stats) = maze_solver_within(
+            &mut grid.clone(),
+            Algorithm::BFS,
+            false,
+            0,
+            Connectivity::Four,
+        )
+        .unwrap();
+        assert_eq!(ones.len(), stats.path_length + 1);
+    }
+
+    #[test]
+    fn tie_breaks_pick_between_two_shortest_paths() {
+        let grid = maze(
+            "
+            ######
+            #A  ##
+            # # ##
+            #   B#
+            ######
+            ",
+        );
+        let over_the_top = vec![(1, 1), (1, 2), (1, 3), (2, 3), (3, 3), (3, 4)];
+        let down_first = vec![(1, 1), (2, 1), (3, 1), (3, 2), (3, 3), (3, 4)];
+        for (prefer, expected) in [
+            (TieBreak::Straight, &down_first),
+            (TieBreak::Turns, &over_the_top),
+            (TieBreak::UpperLeft, &over_the_top),
+            (TieBreak::Center, &over_the_top),
+        ] {
+            assert_eq!(
+                shortest_path_preferring(&grid, prefer).as_ref(),
+                Some(expected),
+                "{prefer:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn adjacent_endpoints_skip_the_search() {
+        let grid = maze(
+            "
+            ######
+            #    #
+            # AB #
+            #    #
+            ######
+            ",
+        );
+        for algorithm in Algorithm::ALL {
+            let mut solved = grid.clone();
+            let (found, stats) =
+                maze_solver_within(&mut solved, algorithm, true, 0, Connectivity::Four).unwrap();
+            assert!(found);
+            assert_eq!(
+                (stats.path_length, stats.nodes_visited),
+                (1, 0),
+                "{algorithm:?}"
+            );
+            assert_eq!(solved, grid);
+        }
+    }
+
+    #[test]
+    fn diagnosis_explains_untouched_cells() {
+        let grid = maze(
+            "
+            #######
+            #A B# #
+            #   # #
+            #D  ###
+            #######
+            ",
+        );
+        let (_, visited, reasons) = diagnose(&grid, Algorithm::BFS).unwrap();
+        assert_eq!(reasons[&(1, 5)], PruneReason::OutOfComponent);
+        assert_eq!(reasons[&(2, 5)], PruneReason::OutOfComponent);
+        assert_eq!(reasons[&(3, 1)], PruneReason::Danger);
+        assert_eq!(reasons[&(0, 0)], PruneReason::Wall);
+        assert_eq!(reasons[&(3, 3)], PruneReason::NotReached);
+        assert!(visited.iter().all(|cell| !reasons.contains_key(cell)));
+    }
+
+    #[test]
+    fn shortest_path_dag_branches_and_merges() {
+        let grid = maze(
+            "
+            ######
+            #A  ##
+            # # ##
+            #   B#
+            ######
+            ",
+        );
+        let mut dag = shortest_path_dag(&grid);
+        dag.values_mut().for_each(|successors| successors.sort());
+        assert_eq!(
+            dag,
+            Dag::from([
+                ((1, 1), vec![(1, 2), (2, 1)]),
+                ((1, 2), vec![(1, 3)]),
+                ((1, 3), vec![(2, 3)]),
+                ((2, 3), vec![(3, 3)]),
+                ((2, 1), vec![(3, 1)]),
+                ((3, 1), vec![(3, 2)]),
+                ((3, 2), vec![(3, 3)]),
+                ((3, 3), vec![(3, 4)]),
+                ((3, 4), vec![]),
+            ])
+        );
+        assert_eq!(shortest_path_dag(&maze("#A#B#")), Dag::new());
+    }
+
+    #[test]
+    fn center_bias_bows_toward_the_middle() {
+        let grid = maze(
+            "
+            #########
+            #A      #
+            #       #
+            #       #
+            #       #
+            #      B#
+            #########
+            ",
+        );
+        let centered = shortest_path_preferring(&grid, TieBreak::Center).unwrap();
+        let upper_left = shortest_path_preferring(&grid, TieBreak::UpperLeft).unwrap();
+        assert_eq!(centered.len(), upper_left.len());
+        assert!(centered.contains(&(3, 4)));
+        let spread = |path: &Path| {
+            path.iter()
+                .map(|&cell| manhattan_dist(cell, (3, 4)))
+                .sum::<usize>()
+        };
+        assert!(spread(&centered) < spread(&upper_left));
+    }
+
+    #[test]
+    fn a_star_detours_around_expensive_terrain() {
+        let grid = maze(
+            "
+            #########
+            #A 999 B#
+            # ##### #
+            #       #
+            #########
+            ",
+        );
+        let cost = |path: &Path| -> usize {
+            path[1..]
+                .iter()
+                .map(|&(row, col)| cell_cost(grid[row][col]))
+                .sum()
+        };
+        let a_star = solve(&grid, Algorithm::AStar).unwrap().unwrap().path;
+        assert!(a_star.iter().all(|&(row, col)| grid[row][col] != '9'));
+        assert_eq!((a_star.len() - 1, cost(&a_star)), (10, 10));
+
+        let bfs = solve(&grid, Algorithm::BFS).unwrap().unwrap().path;
+        assert_eq!((bfs.len() - 1, cost(&bfs)), (6, 30));
+    }
+
+    #[test]
+    fn a_star_is_optimal_where_routes_converge() {
+        let original = maze(
+            "
+            ########
+            #A #   #
+            #      #
+            #   ## #
+            ## #  B#
+            ########
+            ",
+        );
+        let optimum = analysis::shortest_path_length(&original).unwrap();
+        let mut grid = original.clone();
+        maze_solver(&mut grid, Algorithm::AStar, false).unwrap();
+        let marked = grid.iter().flatten().filter(|&&c| c == '*').count();
+        assert_eq!(marked + 1, optimum);
+    }
+
+    #[test]
+    fn dfs_follows_a_long_serpentine_corridor() {
+        // 100 corridors of 398 cells joined at alternating ends.
+        let (rows, cols) = (201, 400);
+        let mut grid = vec![vec![WALL; cols]; rows];
+        for row in (1..rows).step_by(2) {
+            grid[row][1..cols - 1].fill(' ');
+            if row + 2 < rows {
+                let turn = if row % 4 == 1 { cols - 2 } else { 1 };
+                grid[row + 1][turn] = ' ';
+            }
+        }
+        grid[1][1] = START;
+        grid[rows - 2][1] = END;
+
+        let path = solve(&grid, Algorithm::DFS).unwrap().unwrap().path;
+        assert_eq!(path.len(), 100 * 398 + 99);
+        assert_eq!(solve(&grid, Algorithm::BFS).unwrap().unwrap().path, path);
+    }
+
+    #[test]
+    fn missing_endpoints_are_errors() {
+        let passable = |_| true;
+        for algorithm in Algorithm::ALL {
+            let mut no_end = maze("#A #");
+            assert_eq!(
+                maze_solver(&mut no_end, algorithm, false),
+                Err(Error::EndNotFound)
+            );
+            let mut no_start = maze("# B#");
+            assert_eq!(
+                maze_solver(&mut no_start, algorithm, false),
+                Err(Error::StartNotFound)
+            );
+            assert_eq!(
+                maze_solver_with(&mut no_start, algorithm, false, &passable),
+                Err(Error::StartNotFound)
+            );
+        }
+    }
+}
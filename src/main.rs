@@ -1,313 +1,702 @@
 use std::{
-    cmp::Reverse,
-    collections::{BinaryHeap, HashSet, VecDeque},
-    io::Read,
+    collections::HashSet,
+    io::{Read, Write},
+    time::Instant,
 };
 
-#[derive(Debug, Clone, Copy)]
-pub enum Error {
-    InvalidAlgorithm,
-    InvalidCharacter,
-    FileNotFound,
-    MangledRows,
-    StartNotFound,
-    EndNotFound,
-    EmptyMaze,
-}
-
-#[derive(Debug, Clone, Copy)]
-pub enum Algorithm {
-    DFS,
-    BFS,
-    GreedyBestFirst,
-    AStar,
-}
-
-const VALID_CHARS: &'static str = "AB█ ";
-const START: char = 'A';
-const END: char = 'B';
-const DIRECTIONS: [(usize, usize); 4] = [
-    (usize::max_value(), 0),
-    (0, usize::max_value()),
-    (1, 0),
-    (0, 1),
-];
+#[cfg(all(unix, target_pointer_width = "64"))]
+use maze_solver_rs::mmap;
+use maze_solver_rs::{
+    analysis, audit, both_directions, canonical_solution_signature, cell, diagnose, draw_rings,
+    generate, json, locally_minimize_path, maze_solver_within, min_wall_breaks, normalize_output,
+    paint_path, parse_algorithm, path_mask, reachable_in_exactly, read_maze_from, render, rng,
+    scale_maze, shortest_path_preferring, solve_full_within, symmetry, verify_determinism,
+    write_grid, Algorithm, Charset, Connectivity, Error, Path, Stats, TieBreak,
+};
+#[cfg(feature = "tui")]
+use maze_solver_rs::{get_end, get_start, searcher, tui};
 
-fn is_maze_valid(maze: &[Vec<char>]) -> Result<(), Error> {
-    if maze.len() == 0 {
-        Err(Error::EmptyMaze)
-    } else if !maze.iter().all(|row| row.len() == maze[0].len()) {
-        Err(Error::MangledRows)
-    } else if get_start(maze) == None {
-        Err(Error::StartNotFound)
-    } else if get_end(maze) == None {
-        Err(Error::EndNotFound)
-    } else if !maze
-        .iter()
-        .all(|row| row.iter().all(|&c| VALID_CHARS.contains(c)))
-    {
-        Err(Error::InvalidCharacter)
-    } else {
-        Ok(())
+/// Runs `algorithm` on `grid`, applying the post-processing requested in
+/// `options`. Also returns the path painted on the grid when it had to be
+/// chosen separately from the search (`--prefer`, `--center-bias`,
+/// `--trim-equivalent-paths`) or the caller needs it (`--min-width`, the mask
+/// format); `None` otherwise or when there is no path.
+fn solve(
+    grid: &mut [Vec<char>],
+    algorithm: Algorithm,
+    display_visited: bool,
+    options: &Options,
+) -> Result<(bool, Stats, Option<Path>), Error> {
+    let original = options.normalize_output.then(|| grid.to_vec());
+    let needs_path = options.trim_paths || options.min_width || options.format == Format::Mask;
+    let mut replacement = match options.prefer {
+        Some(prefer) if algorithm == Algorithm::BFS => shortest_path_preferring(grid, prefer),
+        // The tie-breaks choose among equally short paths, which only BFS promises.
+        Some(_) => return Err(Error::InvalidArgument),
+        None if needs_path => {
+            solve_full_within(grid, algorithm, options.goal_radius, options.connectivity)?.1
+        }
+        None => None,
+    };
+    if options.trim_paths {
+        replacement =
+            replacement.map(|path| locally_minimize_path(grid, &path, options.connectivity));
     }
+    let (solved, mut stats) = maze_solver_within(
+        grid,
+        algorithm,
+        display_visited,
+        options.goal_radius,
+        options.connectivity,
+    )?;
+    if let Some(path) = &replacement {
+        let unmarked = if display_visited { '@' } else { ' ' };
+        grid.iter_mut()
+            .flatten()
+            .filter(|c| **c == '*')
+            .for_each(|c| *c = unmarked);
+        paint_path(grid, path);
+        stats.path_length = path.len() - 1;
+    }
+    if let Some(original) = original {
+        normalize_output(&original, grid, display_visited);
+    }
+    if options.rings {
+        draw_rings(grid);
+    }
+    Ok((solved, stats, replacement))
 }
 
-fn get_start(maze: &[Vec<char>]) -> Option<(usize, usize)> {
-    for (rowi, row) in maze.iter().enumerate() {
-        for (coli, ele) in row.iter().copied().enumerate() {
-            if ele == START {
-                return Some((rowi, coli));
-            }
+/// Reads the maze at `path`, or from stdin if `path` is `-`, as a token grid
+/// with `--token-grid`.
+fn load(path: &str, options: &Options) -> Result<Vec<Vec<char>>, Error> {
+    let mut reader: Box<dyn Read> = match path {
+        "-" => Box::new(std::io::stdin().lock()),
+        path => Box::new(std::fs::File::open(path).map_err(|_| Error::FileNotFound)?),
+    };
+    match options.token_grid {
+        true => {
+            let mut contents = String::new();
+            let _ = reader.read_to_string(&mut contents);
+            cell::parse_token_grid(&contents)
         }
+        false => read_maze_from(reader, options.charset),
     }
-    None
 }
 
-fn get_end(maze: &[Vec<char>]) -> Option<(usize, usize)> {
-    for (rowi, row) in maze.iter().enumerate() {
-        for (coli, ele) in row.iter().copied().enumerate() {
-            if ele == END {
-                return Some((rowi, coli));
-            }
-        }
-    }
-    None
+/// Streams `grid` to stdout through a buffer, optionally headed by `title:`,
+/// followed by a blank line.
+fn print_grid(grid: &[Vec<char>], title: Option<&str>) -> Result<(), Error> {
+    let mut out = std::io::BufWriter::new(std::io::stdout().lock());
+    title
+        .map_or(Ok(()), |title| writeln!(out, "{title}:"))
+        .and_then(|_| write_grid(grid, &mut out))
+        .and_then(|_| writeln!(out))
+        .and_then(|_| out.flush())
+        .map_err(|_| Error::OutputFailed)
 }
 
-fn dfs(
-    maze: &mut [Vec<char>],
-    (row, col): (usize, usize),
-    display_visited: bool,
-    vis: &mut HashSet<(usize, usize)>,
-) -> bool {
-    if row >= maze.len()
-        || col >= maze[row].len()
-        || maze[row][col] == '@'
-        || maze[row][col] == '█'
-        || vis.contains(&(row, col))
-    {
-        return false;
+/// Reads the set of already solved files from a checkpoint, one path per line.
+/// A missing checkpoint simply means nothing has been solved yet.
+fn load_checkpoint(path: &str) -> Result<HashSet<String>, Error> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => Ok(contents.lines().map(String::from).collect()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(HashSet::new()),
+        Err(_) => Err(Error::CheckpointFailed),
     }
+}
 
-    if maze[row][col] == END {
-        return true;
-    }
+fn mark_done(checkpoint: &str, file: &str) -> Result<(), Error> {
+    let mut out = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(checkpoint)
+        .map_err(|_| Error::CheckpointFailed)?;
+    writeln!(out, "{file}").map_err(|_| Error::CheckpointFailed)
+}
 
-    vis.insert((row, col));
+/// Solves every file in `files`, skipping the ones already recorded in the
+/// checkpoint, and records each file right after it is solved so an
+/// interrupted run picks up where it left off. Once every file has been
+/// tried, fails with the error of the first file that could not be loaded, or
+/// else with `Error::NoPathFound` if any of them had no solution.
+fn run_batch(
+    files: &[String],
+    algorithm: Algorithm,
+    display_visited: bool,
+    options: &Options,
+) -> Result<(), Error> {
+    let done = match options.checkpoint {
+        Some(path) => load_checkpoint(path)?,
+        None => HashSet::new(),
+    };
 
-    if maze[row][col] != START {
-        maze[row][col] = '@';
+    let mut load_failed = None;
+    let mut unsolvable = false;
+    for file in files.iter().filter(|file| !done.contains(*file)) {
+        let started = Instant::now();
+        let mut grid = match load(file, options) {
+            Ok(grid) => grid,
+            Err(e) => {
+                match options.format {
+                    Format::Jsonl => println!(
+                        "{{\"file\":{},\"error\":{}}}",
+                        json::string(file),
+                        json::string(&format!("{e:?}"))
+                    ),
+                    _ => eprintln!("{file}: {e:?}"),
+                }
+                load_failed = load_failed.or(Some(e));
+                continue;
+            }
+        };
+        let (solved, stats, _) = solve(&mut grid, algorithm, display_visited, options)?;
+        if options.format == Format::Jsonl {
+            let path_length = match solved {
+                true => stats.path_length.to_string(),
+                false => "null".to_string(),
+            };
+            println!(
+                "{{\"file\":{},\"solved\":{solved},\"path_length\":{path_length},\"elapsed_ms\":{:.3}}}",
+                json::string(file),
+                started.elapsed().as_secs_f64() * 1000.0
+            );
+        } else {
+            print_grid(&grid, Some(file))?;
+            if !solved {
+                eprintln!("{file}: no path from A to B");
+            }
+        }
+        unsolvable |= !solved;
+        if let Some(path) = options.checkpoint {
+            mark_done(path, file)?;
+        }
+    }
+    match (load_failed, unsolvable) {
+        (Some(e), _) => Err(e),
+        (None, true) => Err(Error::NoPathFound),
+        (None, false) => Ok(()),
     }
+}
 
-    let res = DIRECTIONS.iter().any(|&(dx, dy)| {
-        dfs(
-            maze,
-            (row.overflowing_add(dx).0, col.overflowing_add(dy).0),
-            display_visited,
-            vis,
-        )
-    });
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    #[default]
+    Text,
+    Json,
+    /// One JSON object per line, for batch runs.
+    Jsonl,
+    /// The solution as a grid of `0`/`1`, without the maze.
+    Mask,
+}
 
-    if !display_visited && maze[row][col] != START {
-        maze[row][col] = ' ';
-    }
+#[derive(Debug, Default)]
+struct Options<'a> {
+    format: Format,
+    analyze: bool,
+    audit: bool,
+    symmetry: bool,
+    exact_steps: Option<usize>,
+    checkpoint: Option<&'a str>,
+    normalize_output: bool,
+    rings: bool,
+    trim_paths: bool,
+    prefer: Option<TieBreak>,
+    dead_ends: bool,
+    rotate: Option<symmetry::Symmetry>,
+    break_walls: bool,
+    goal_radius: usize,
+    min_width: bool,
+    both_directions: bool,
+    profile: Option<&'a str>,
+    scale: Option<usize>,
+    verify_determinism: Option<usize>,
+    random_ties: bool,
+    seed: Option<u64>,
+    signature: bool,
+    interactive: bool,
+    connectivity: Connectivity,
+    difficulty: generate::Difficulty,
+    diagnose: bool,
+    token_grid: bool,
+    clipboard: bool,
+    live_stats: bool,
+    mmap: bool,
+    charset: Charset,
+}
 
-    if res && maze[row][col] != START {
-        maze[row][col] = '*';
+/// The value of a flag that takes exactly one character.
+fn single_char(arg: Option<&String>) -> Result<char, Error> {
+    let mut chars = arg.ok_or(Error::InvalidArgument)?.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(Error::InvalidArgument),
     }
-
-    res
 }
 
-fn bfs(maze: &mut [Vec<char>], start: (usize, usize), display_visited: bool) -> bool {
-    let mut frontier: VecDeque<(usize, usize, Vec<(usize, usize)>)> =
-        VecDeque::from([(start.0, start.1, vec![])]);
-    let mut visited: HashSet<(usize, usize)> = HashSet::new();
-    while let Some((row, col, mut path)) = frontier.pop_front() {
-        if maze[row][col] == END {
-            for (ri, ci) in path.into_iter().skip(1) {
-                maze[ri][ci] = '*';
+/// Splits the command line into positional arguments and `--flag` options.
+fn parse_options(args: &[String]) -> Result<(Vec<&str>, Options<'_>), Error> {
+    let mut options = Options::default();
+    let mut positional = vec![];
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--checkpoint" => {
+                options.checkpoint = Some(args.next().ok_or(Error::InvalidArgument)?.as_str())
             }
-            return true;
-        }
-
-        if display_visited && maze[row][col] != START {
-            maze[row][col] = '@';
+            "--format" => {
+                options.format = match args.next().map(String::as_str) {
+                    Some("text") => Format::Text,
+                    Some("json") => Format::Json,
+                    Some("jsonl") => Format::Jsonl,
+                    Some("mask") => Format::Mask,
+                    _ => return Err(Error::InvalidArgument),
+                }
+            }
+            "--analyze" => options.analyze = true,
+            "--audit" => options.audit = true,
+            "--symmetry" => options.symmetry = true,
+            "--dead-ends" => options.dead_ends = true,
+            "--diagonal" => options.connectivity = Connectivity::Eight,
+            "--difficulty" => {
+                options.difficulty = match args.next().map(String::as_str) {
+                    Some("easy") => generate::Difficulty::Easy,
+                    Some("medium") => generate::Difficulty::Medium,
+                    Some("hard") => generate::Difficulty::Hard,
+                    _ => return Err(Error::InvalidArgument),
+                }
+            }
+            "--exact-steps" => {
+                options.exact_steps = Some(
+                    args.next()
+                        .and_then(|k| k.parse().ok())
+                        .ok_or(Error::InvalidArgument)?,
+                )
+            }
+            "--normalize-output" => options.normalize_output = true,
+            "--profile" => {
+                options.profile = Some(args.next().ok_or(Error::InvalidArgument)?.as_str())
+            }
+            "--rings" => options.rings = true,
+            "--solve-both-directions" => options.both_directions = true,
+            "--scale" => {
+                options.scale = Some(
+                    args.next()
+                        .and_then(|n| n.parse().ok())
+                        .filter(|&n| n > 0)
+                        .ok_or(Error::InvalidArgument)?,
+                )
+            }
+            "--trim-equivalent-paths" => options.trim_paths = true,
+            "--verify-determinism" => {
+                options.verify_determinism = Some(
+                    args.next()
+                        .and_then(|runs| runs.parse().ok())
+                        .ok_or(Error::InvalidArgument)?,
+                )
+            }
+            "--rotate" => {
+                options.rotate = Some(match args.next().map(String::as_str) {
+                    Some("90") => symmetry::Symmetry::Rotate90,
+                    Some("180") => symmetry::Symmetry::Rotate180,
+                    Some("270") => symmetry::Symmetry::Rotate270,
+                    _ => return Err(Error::InvalidArgument),
+                })
+            }
+            // Only one tie-break policy can apply.
+            "--prefer" | "--center-bias" if options.prefer.is_some() => {
+                return Err(Error::InvalidArgument)
+            }
+            "--prefer" => {
+                options.prefer = Some(match args.next().map(String::as_str) {
+                    Some("straight") => TieBreak::Straight,
+                    Some("turns") => TieBreak::Turns,
+                    Some("upperleft") => TieBreak::UpperLeft,
+                    _ => return Err(Error::InvalidArgument),
+                })
+            }
+            "--center-bias" => options.prefer = Some(TieBreak::Center),
+            "--random-ties" => options.random_ties = true,
+            "--seed" => {
+                options.seed = Some(
+                    args.next()
+                        .and_then(|seed| seed.parse().ok())
+                        .ok_or(Error::InvalidArgument)?,
+                )
+            }
+            "--signature" => options.signature = true,
+            "--goal-radius" => {
+                options.goal_radius = args
+                    .next()
+                    .and_then(|r| r.parse().ok())
+                    .ok_or(Error::InvalidArgument)?
+            }
+            "--interactive" => options.interactive = true,
+            "--diagnose" => options.diagnose = true,
+            "--token-grid" => options.token_grid = true,
+            "--clipboard" => options.clipboard = true,
+            "--live-stats" => options.live_stats = true,
+            "--break-walls" => options.break_walls = true,
+            "--min-width" => options.min_width = true,
+            "--mmap" => options.mmap = true,
+            "--start" => options.charset.start = single_char(args.next())?,
+            "--end" => options.charset.end = single_char(args.next())?,
+            "--wall" => options.charset.wall = single_char(args.next())?,
+            _ => positional.push(arg.as_str()),
         }
+    }
+    Ok((positional, options))
+}
 
-        path.push((row, col));
-
-        DIRECTIONS
-            .iter()
-            .copied()
-            .map(|(dx, dy)| (row.overflowing_add(dx).0, col.overflowing_add(dy).0))
-            .filter(|&(row, col)| {
-                row < maze.len() && col < maze[row].len() && maze[row][col] != '█'
-            })
-            .for_each(|(row, col)| {
-                if !visited.contains(&(row, col)) {
-                    visited.insert((row, col));
-                    frontier.push_back((row, col, path.clone()))
-                }
-            });
+fn batch(args: &[String]) -> Result<(), Error> {
+    let (positional, options) = parse_options(args)?;
+    if positional.len() < 2 {
+        return Err(Error::InvalidArgument);
     }
-    false
+    let algorithm = parse_algorithm(positional[0])?;
+    let display_visited: bool = positional[1].parse().map_err(|_| Error::InvalidArgument)?;
+    let files = positional[2..]
+        .iter()
+        .map(|f| f.to_string())
+        .collect::<Vec<_>>();
+    run_batch(&files, algorithm, display_visited, &options)
 }
-fn manhattan_dist(p1: (usize, usize), p2: (usize, usize)) -> usize {
-    (if p1.0 > p2.0 {
-        p1.0 - p2.0
-    } else {
-        p2.0 - p1.0
-    }) + (if p1.1 > p2.1 {
-        p1.1 - p2.1
-    } else {
-        p2.1 - p1.1
-    })
+
+fn generate(args: &[String]) -> Result<(), Error> {
+    let (positional, options) = parse_options(args)?;
+    let [rows, cols] = positional[..] else {
+        return Err(Error::InvalidArgument);
+    };
+    let size = |n: &str| n.parse::<usize>().map_err(|_| Error::InvalidArgument);
+    let mut rng = rng::Seeds::new(options.seed).rng("generate");
+    let maze = generate::generate(size(rows)?, size(cols)?, options.difficulty, &mut rng);
+    // No trailing blank line, so the output can be fed straight back in.
+    write_grid(&maze, &mut std::io::stdout().lock()).map_err(|_| Error::OutputFailed)
 }
-fn greedy_best_first_search(
-    maze: &mut [Vec<char>],
-    (start_row, start_col): (usize, usize),
-    (end_row, end_col): (usize, usize),
-    display_visited: bool,
-) -> bool {
-    let mut frontier: BinaryHeap<(Reverse<usize>, usize, usize, Vec<(usize, usize)>)> =
-        BinaryHeap::from([(
-            Reverse(manhattan_dist((start_row, start_col), (end_row, end_col))),
-            start_row,
-            start_col,
-            vec![],
-        )]);
-    let mut visited: HashSet<(usize, usize)> = HashSet::new();
 
-    while let Some((_, row, col, mut path)) = frontier.pop() {
-        if maze[row][col] == END {
-            for (ri, ci) in path.into_iter().skip(1) {
-                maze[ri][ci] = '*';
-            }
-            return true;
-        }
+#[cfg(feature = "tui")]
+fn interactive(grid: &[Vec<char>], algorithm: Algorithm, live_stats: bool) -> Result<(), Error> {
+    let searcher = searcher::Searcher::new(
+        grid,
+        algorithm,
+        get_start(grid).unwrap(),
+        get_end(grid).unwrap(),
+    );
+    tui::run(tui::Session::new(grid, searcher), live_stats)
+}
 
-        if display_visited && maze[row][col] != START {
-            maze[row][col] = '@';
-        }
+#[cfg(not(feature = "tui"))]
+fn interactive(_: &[Vec<char>], _: Algorithm, _: bool) -> Result<(), Error> {
+    eprintln!("--interactive requires building with `--features tui`");
+    Err(Error::FeatureDisabled)
+}
 
-        path.push((row, col));
-        DIRECTIONS
-            .iter()
-            .copied()
-            .map(|(dx, dy)| (row.overflowing_add(dx).0, col.overflowing_add(dy).0))
-            .filter(|&(row, col)| {
-                row < maze.len() && col < maze[row].len() && maze[row][col] != '█'
-            })
-            .for_each(|(row, col)| {
-                if !visited.contains(&(row, col)) {
-                    visited.insert((row, col));
-                    frontier.push((
-                        Reverse(manhattan_dist((row, col), (end_row, end_col))),
-                        row,
-                        col,
-                        path.clone(),
-                    ))
-                }
-            });
+#[cfg(all(unix, target_pointer_width = "64"))]
+fn solve_mmap(path: &str) -> Result<(), Error> {
+    let maze = mmap::MmapMaze::open(path)?;
+    let path = mmap::bfs(&maze)?;
+    let mut out = std::io::BufWriter::new(std::io::stdout().lock());
+    maze.write_solved(path.as_deref().unwrap_or_default(), &mut out)
+        .and_then(|_| out.flush())
+        .map_err(|_| Error::MmapFailed)?;
+    if path.is_none() {
+        eprintln!("no path from A to B");
+        return Err(Error::NoPathFound);
     }
-
-    false
+    Ok(())
 }
-fn a_star(
-    maze: &mut [Vec<char>],
-    (start_row, start_col): (usize, usize),
-    (end_row, end_col): (usize, usize),
-    display_visited: bool,
-) -> bool {
-    let mut frontier: BinaryHeap<(Reverse<usize>, usize, usize, Vec<(usize, usize)>)> =
-        BinaryHeap::from([(
-            Reverse(manhattan_dist((start_row, start_col), (end_row, end_col))),
-            start_row,
-            start_col,
-            vec![],
-        )]);
-    let mut visited: HashSet<(usize, usize)> = HashSet::new();
 
-    while let Some((_, row, col, mut path)) = frontier.pop() {
-        if maze[row][col] == END {
-            for (ri, ci) in path.into_iter().skip(1) {
-                maze[ri][ci] = '*';
-            }
-            return true;
-        }
+#[cfg(not(all(unix, target_pointer_width = "64")))]
+fn solve_mmap(_: &str) -> Result<(), Error> {
+    Err(Error::FeatureDisabled)
+}
 
-        if display_visited && maze[row][col] != START {
-            maze[row][col] = '@';
-        }
+#[cfg(feature = "clipboard")]
+fn copy_to_clipboard(text: &str) -> Result<(), Error> {
+    maze_solver_rs::clipboard::copy(text)
+}
 
-        path.push((row, col));
-        DIRECTIONS
-            .iter()
-            .copied()
-            .map(|(dx, dy)| (row.overflowing_add(dx).0, col.overflowing_add(dy).0))
-            .filter(|&(row, col)| {
-                row < maze.len() && col < maze[row].len() && maze[row][col] != '█'
-            })
-            .for_each(|(row, col)| {
-                if !visited.contains(&(row, col)) {
-                    visited.insert((row, col));
-                    frontier.push((
-                        Reverse(manhattan_dist((row, col), (end_row, end_col)) + path.len()),
-                        row,
-                        col,
-                        path.clone(),
-                    ))
-                }
-            });
-    }
+#[cfg(not(feature = "clipboard"))]
+fn copy_to_clipboard(_: &str) -> Result<(), Error> {
+    eprintln!("--clipboard requires building with `--features clipboard`");
+    Err(Error::FeatureDisabled)
+}
 
-    false
+#[cfg(feature = "profile")]
+fn write_profile(path: &str) -> Result<(), Error> {
+    std::fs::write(path, maze_solver_rs::profile::folded()).map_err(|_| Error::OutputFailed)
 }
-fn maze_solver(maze: &mut [Vec<char>], algorithm: Algorithm, display_visited: bool) {
-    let start = get_start(maze).unwrap();
-    let end = get_end(maze).unwrap();
 
-    match algorithm {
-        Algorithm::DFS => _ = dfs(maze, start, display_visited, &mut HashSet::new()),
-        Algorithm::BFS => _ = bfs(maze, start, display_visited),
-        Algorithm::GreedyBestFirst => {
-            _ = greedy_best_first_search(maze, start, end, display_visited)
-        }
-        Algorithm::AStar => _ = a_star(maze, start, end, display_visited),
-    }
+#[cfg(not(feature = "profile"))]
+fn write_profile(_: &str) -> Result<(), Error> {
+    eprintln!("--profile requires building with `--features profile`");
+    Err(Error::FeatureDisabled)
 }
 
+const USAGE: &str = "\
+Usage: maze-solver-rs <filename, or - for stdin> <algoithm name: A*, DFS, BFS, GBFS> <display visited?> [options]
+       maze-solver-rs batch <algorithm> <display visited?> [options] <filenames...>
+       maze-solver-rs generate <rows> <cols> [--difficulty <d>] [--seed <n>]
+
+Options:
+    --analyze             print a structural report of the maze instead of solving it
+    --audit               run every algorithm and check BFS finds the fewest steps and A* the lowest cost
+    --break-walls         print the fewest walls to remove so that A connects to B
+    --center-bias         (BFS) among equally short paths, take the one closest to the centre;
+                          not with --prefer
+    --checkpoint <file>   (batch) record solved files and skip them on the next run
+    --live-stats          (with --interactive) show expansions, frontier size and elapsed time
+    --min-width           print the narrowest corridor width along the found path
+    --mmap                solve with BFS directly from a memory-mapped ASCII file (for huge mazes)
+    --normalize-output    print the solved maze in a canonical form
+    --prefer <straight|turns|upperleft>
+                          (BFS) among equally short paths, take the one with the fewest turns,
+                          the most turns, or the smallest coordinates
+    --profile <file>      write per-phase solver timings as folded stacks (requires the `profile` feature)
+    --random-ties         (with --verify-determinism) break frontier ties randomly
+    --rotate <90|180|270> print the solved maze rotated clockwise
+    --rings               label reachable cells with their BFS distance from the start
+    --clipboard           also copy the solved maze to the clipboard (requires the `clipboard` feature)
+    --start <c>, --end <c>, --wall <c>
+                          read the start, end and walls as these characters instead of A, B and #
+    --dead-ends           print the number of dead ends and the maze with them marked `x`
+    --diagonal            allow diagonal steps between cells; not supported by --prefer, --center-bias,
+                          --signature, --solve-both-directions, --verify-determinism, --diagnose,
+                          --interactive, --rings, --exact-steps, --analyze, --break-walls,
+                          --dead-ends, --symmetry or --mmap
+    --diagnose            show why each untouched cell was not expanded (x: unreachable, .: not reached)
+    --difficulty <easy|medium|hard>
+                          (generate) loops and long corridors for easy, branches and dead ends for hard
+    --exact-steps <k>     print whether B can be reached in exactly k steps (revisits allowed)
+    --format <text|json|jsonl|mask>
+                          output format for reports; jsonl prints one result per line in batch runs,
+                          mask prints the path as a grid of 1s over 0s instead of the solved maze
+    --goal-radius <r>     stop as soon as the path is within r steps of B (not with --prefer or --center-bias)
+    --interactive         step through the search in the terminal (requires the `tui` feature)
+    --solve-both-directions
+                          solve A to B and B to A and fail if the path lengths differ (BFS or A* only)
+    --symmetry            print which flips and rotations leave the walls unchanged
+    --token-grid          read cells as whitespace separated tokens (WW wall, .. open, AA, BB, ...)
+    --trim-equivalent-paths
+                          cut detours out of the found path until no cell can be bypassed
+    --verify-determinism <runs>
+                          solve repeatedly and fail if any run differs from the first
+    --seed <n>            seed every randomized feature so the whole run is reproducible
+    --scale <n>           solve an upscaled copy where every cell is an n x n block
+    --signature           print a solution signature that is invariant under rotation/reflection
+";
+
 fn main() -> Result<(), Error> {
     let args: Vec<String> = std::env::args().skip(1).collect();
-    if args.len() == 0 || args[0] == "-h" || args[0] == "--help" {
-        println!("Usage: maze-solver-rs <filename> <algoithm name: A*, DFS, BFS, GBFS> <display visited?>")
+    if args.is_empty() || args[0] == "-h" || args[0] == "--help" {
+        print!("{USAGE}");
+        return Ok(());
     }
-    let mut file = std::fs::File::open(&args[0]).map_err(|_| Error::FileNotFound)?;
-    let mut contents = String::new();
-    let display_visited: bool = args[2].parse().unwrap();
-    let _ = file.read_to_string(&mut contents);
-
-    let mut grid = contents
-        .lines()
-        .map(str::chars)
-        .map(|x| x.map(|c| if c == '#' { '█' } else { c }))
-        .map(Iterator::collect::<Vec<char>>)
-        .collect::<Vec<Vec<char>>>();
-
-    is_maze_valid(&grid)?;
-    match args[1].as_str() {
-        "A*" => maze_solver(&mut grid, Algorithm::AStar, display_visited),
-        "BFS" => maze_solver(&mut grid, Algorithm::BFS, display_visited),
-        "DFS" => maze_solver(&mut grid, Algorithm::DFS, display_visited),
-        "GBFS" => maze_solver(&mut grid, Algorithm::GreedyBestFirst, display_visited),
-        _ => return Err(Error::InvalidAlgorithm),
+    if args[0] == "batch" {
+        return batch(&args[1..]);
+    }
+    if args[0] == "generate" {
+        return generate(&args[1..]);
+    }
+    let (args, options) = parse_options(&args)?;
+    let Some(&file) = args.first() else {
+        return Err(Error::InvalidArgument);
+    };
+    // These only know how to walk four-neighbour paths.
+    let four_only = options.prefer.is_some()
+        || options.signature
+        || options.both_directions
+        || options.verify_determinism.is_some()
+        || options.diagnose
+        || options.interactive
+        || options.rings
+        || options.exact_steps.is_some()
+        || options.analyze
+        || options.break_walls
+        || options.dead_ends
+        || options.symmetry
+        || options.mmap;
+    if options.connectivity == Connectivity::Eight && four_only {
+        return Err(Error::InvalidArgument);
+    }
+    // The preferred shortest paths always run all the way to B.
+    if options.goal_radius > 0 && options.prefer.is_some() {
+        return Err(Error::InvalidArgument);
+    }
+    if options.mmap {
+        // The mapping reads the default characters from a real file.
+        if file == "-" || options.charset != Charset::default() {
+            return Err(Error::InvalidArgument);
+        }
+        return solve_mmap(file);
+    }
+    if options.analyze {
+        let report = analysis::Analysis::of(&load(file, &options)?);
+        match options.format {
+            Format::Text | Format::Mask => println!("{report}"),
+            Format::Json | Format::Jsonl => println!("{}", report.to_json()),
+        }
+        return Ok(());
+    }
+    if options.audit {
+        let audit = audit(&load(file, &options)?, options.connectivity)?;
+        for (&(algorithm, len), &(_, cost)) in audit.lengths.iter().zip(&audit.costs) {
+            let cost = match cost {
+                Some(cost) if Some(cost) != len => format!(" cost={cost}"),
+                _ => String::new(),
+            };
+            let len = len.map_or("none".to_string(), |len| len.to_string());
+            let note = if algorithm.is_optimal() {
+                " (optimal)"
+            } else {
+                ""
+            };
+            println!(
+                "{:<5} path={len}{cost}{note}",
+                format!("{}:", algorithm.name())
+            );
+        }
+        println!("audit: {}", if audit.passed { "pass" } else { "FAIL" });
+        return match audit.passed {
+            true => Ok(()),
+            false => Err(Error::AuditFailed),
+        };
+    }
+    if let Some(k) = options.exact_steps {
+        let reachable = reachable_in_exactly(&load(file, &options)?, k);
+        println!("reachable in exactly {k} steps: {reachable}");
+        return Ok(());
+    }
+    if options.symmetry {
+        let symmetries = symmetry::maze_symmetries(&load(file, &options)?, false);
+        let names = symmetries.iter().map(|s| s.name()).collect::<Vec<_>>();
+        match names.is_empty() {
+            true => println!("symmetries: none"),
+            false => println!("symmetries: {}", names.join(", ")),
+        }
+        return Ok(());
+    }
+    if options.break_walls {
+        match min_wall_breaks(&load(file, &options)?) {
+            None => println!("walls to break: none connect A to B"),
+            Some(walls) => {
+                println!("walls to break: {}", walls.len());
+                for (row, col) in walls {
+                    println!("  row {row}, col {col}");
+                }
+            }
+        }
+        return Ok(());
+    }
+    if options.dead_ends {
+        let mut grid = load(file, &options)?;
+        let dead_ends = analysis::dead_ends(&grid);
+        println!("dead ends: {}", dead_ends.len());
+        for (row, col) in dead_ends {
+            grid[row][col] = 'x';
+        }
+        return print_grid(&grid, None);
+    }
+    let [_, algorithm, display_visited] = args[..] else {
+        return Err(Error::InvalidArgument);
+    };
+    let algorithm = parse_algorithm(algorithm)?;
+    let display_visited: bool = display_visited
+        .parse()
+        .map_err(|_| Error::InvalidArgument)?;
+    let mut grid = load(file, &options)?;
+    if let Some(n) = options.scale {
+        grid = scale_maze(&grid, n);
+    }
+    if options.both_directions {
+        let (forward, backward) = both_directions(&grid, algorithm)?;
+        let show = |len: Option<usize>| len.map_or("none".to_string(), |len| len.to_string());
+        println!("A->B: {}, B->A: {}", show(forward), show(backward));
+        return match forward == backward {
+            true => Ok(()),
+            false => Err(Error::DirectionalAsymmetry),
+        };
+    }
+    if let Some(runs) = options.verify_determinism {
+        return match verify_determinism(
+            &grid,
+            algorithm,
+            runs,
+            options.random_ties,
+            rng::Seeds::new(options.seed),
+        )? {
+            None => {
+                println!("deterministic across {runs} runs");
+                Ok(())
+            }
+            Some(run) => {
+                println!("nondeterministic: run {run} differs from run 0");
+                Err(Error::Nondeterministic)
+            }
+        };
+    }
+    if options.diagnose {
+        let (path, visited, reasons) = diagnose(&grid, algorithm)?;
+        for (row, col) in visited {
+            if grid[row][col] == ' ' {
+                grid[row][col] = '@';
+            }
+        }
+        paint_path(&mut grid, &path.unwrap_or_default());
+        for ((row, col), reason) in reasons {
+            grid[row][col] = reason.glyph();
+        }
+        print_grid(&grid, None)?;
+        println!("x: no way there from A, .: not reached before B was found");
+        return Ok(());
+    }
+    if options.interactive {
+        return interactive(&grid, algorithm, options.live_stats);
+    }
+    if options.format == Format::Mask {
+        let (_, _, path) = solve(&mut grid.clone(), algorithm, false, &options)?;
+        print_grid(&path_mask(&grid, path.as_deref().unwrap_or_default()), None)?;
+        if path.is_none() {
+            eprintln!("no path from A to B");
+            return Err(Error::NoPathFound);
+        }
+        return Ok(());
+    }
+    let signature = options
+        .signature
+        .then(|| canonical_solution_signature(&grid));
+    let (solved, stats, path) = solve(&mut grid, algorithm, display_visited, &options)?;
+    let min_width = options
+        .min_width
+        .then(|| path.and_then(|path| analysis::min_corridor_width(&grid, &path)));
+    if let Some(rotation) = options.rotate {
+        grid = rotation.apply_to_maze(&grid);
     }
+    print_grid(&grid, None)?;
     println!(
-        "{}",
-        grid.into_iter()
-            .map(|row| row.into_iter().collect::<String>() + "\n")
-            .collect::<String>()
+        "{}: {}, path={}, visited={}, peak frontier={}",
+        algorithm.name(),
+        if solved { "solved" } else { "unsolvable" },
+        stats.path_length,
+        stats.nodes_visited,
+        stats.max_frontier_size
     );
+    if options.clipboard {
+        copy_to_clipboard(&render(&grid))?;
+    }
+    if let Some(path) = options.profile {
+        write_profile(path)?;
+    }
+    if let Some(width) = min_width {
+        let width = width.map_or("none".to_string(), |w| w.to_string());
+        println!("min corridor width: {width}");
+    }
+    if let Some(signature) = signature {
+        println!("signature: {}", signature.as_deref().unwrap_or("none"));
+    }
+    if !solved {
+        eprintln!("no path from A to B");
+        return Err(Error::NoPathFound);
+    }
     Ok(())
 }
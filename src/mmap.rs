@@ -0,0 +1,268 @@
+//! Solving mazes straight from a memory-mapped file, for inputs too large to
+//! parse into a `Vec<Vec<char>>`. The file is mapped read-only and cells are
+//! read in place, so only the search bookkeeping lives on the heap: one bit
+//! per cell for the visited set and two bits per cell for the direction each
+//! cell was reached from.
+//!
+//! Only ASCII mazes are supported (walls written as `'#'`, since `'█'` is
+//! several bytes wide), and every row must have the same width. Terrain
+//! digits are accepted and, as in the crate's BFS, cost nothing extra.
+//!
+//! The module is only built on 64-bit Unix, where `off_t` is 64 bits wide and
+//! matches the hand-written `mmap` declaration below.
+
+use std::{collections::VecDeque, ffi::c_void, fs::File, os::fd::AsRawFd};
+
+use crate::{is_blocked, Error, DIRECTIONS, END, RESERVED_CHARS, START, WALL};
+
+extern "C" {
+    fn mmap(addr: *mut c_void, len: usize, prot: i32, flags: i32, fd: i32, off: i64)
+        -> *mut c_void;
+    fn munmap(addr: *mut c_void, len: usize) -> i32;
+}
+
+const PROT_READ: i32 = 1;
+const MAP_PRIVATE: i32 = 2;
+const MAP_FAILED: *mut c_void = !0 as *mut c_void;
+
+/// A maze backed by a read-only mapping of its file.
+pub struct MmapMaze {
+    data: *const u8,
+    len: usize,
+    rows: usize,
+    cols: usize,
+    stride: usize,
+}
+
+impl MmapMaze {
+    pub fn open(path: &str) -> Result<Self, Error> {
+        let file = File::open(path).map_err(|_| Error::FileNotFound)?;
+        let len = file.metadata().map_err(|_| Error::FileNotFound)?.len() as usize;
+        if len == 0 {
+            return Err(Error::EmptyMaze);
+        }
+        // SAFETY: a fresh read-only private mapping of a file we just opened;
+        // the result is checked against MAP_FAILED before use.
+        let data = unsafe {
+            mmap(
+                std::ptr::null_mut(),
+                len,
+                PROT_READ,
+                MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if data == MAP_FAILED {
+            return Err(Error::MmapFailed);
+        }
+        // SAFETY: the mapping is `len` bytes long and lives until `drop`.
+        let bytes = unsafe { std::slice::from_raw_parts(data as *const u8, len) };
+        let content = bytes.strip_suffix(b"\n").unwrap_or(bytes).len();
+        let cols = bytes.iter().position(|&b| b == b'\n').unwrap_or(content);
+        let stride = cols + 1;
+        let rows = (content + 1) / stride;
+        let maze = Self {
+            data: data as *const u8,
+            len,
+            rows,
+            cols,
+            stride,
+        };
+
+        if cols == 0 {
+            return Err(Error::EmptyMaze);
+        }
+        if (content + 1) % stride != 0
+            || (1..rows).any(|row| maze.bytes()[row * stride - 1] != b'\n')
+        {
+            return Err(Error::MangledRows);
+        }
+        if let Some(&b) = (0..rows)
+            .flat_map(|row| maze.row(row))
+            .find(|&&b| RESERVED_CHARS.contains(b as char))
+        {
+            return Err(Error::ReservedCharacter(b as char));
+        }
+        if !(0..rows).all(|row| {
+            maze.row(row)
+                .iter()
+                .all(|&b| b"AB# D".contains(&b) || (b'1'..=b'9').contains(&b))
+        }) {
+            return Err(Error::InvalidCharacter);
+        }
+        Ok(maze)
+    }
+
+    fn bytes(&self) -> &[u8] {
+        // SAFETY: see `open`.
+        unsafe { std::slice::from_raw_parts(self.data, self.len) }
+    }
+
+    fn row(&self, row: usize) -> &[u8] {
+        &self.bytes()[row * self.stride..row * self.stride + self.cols]
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// The cell at `(row, col)`, with walls read as `'█'` like the text parser does.
+    pub fn get(&self, (row, col): (usize, usize)) -> char {
+        match self.bytes()[row * self.stride + col] {
+            b'#' => WALL,
+            b => b as char,
+        }
+    }
+
+    fn find(&self, c: char) -> Option<(usize, usize)> {
+        (0..self.rows).find_map(|row| {
+            self.row(row)
+                .iter()
+                .position(|&b| b as char == c)
+                .map(|col| (row, col))
+        })
+    }
+
+    pub fn start(&self) -> Option<(usize, usize)> {
+        self.find(START)
+    }
+
+    pub fn end(&self) -> Option<(usize, usize)> {
+        self.find(END)
+    }
+
+    fn neighbours(
+        &self,
+        (row, col): (usize, usize),
+    ) -> impl Iterator<Item = (u8, (usize, usize))> + '_ {
+        DIRECTIONS
+            .iter()
+            .enumerate()
+            .map(move |(d, &(dx, dy))| (d as u8, (row.wrapping_add(dx), col.wrapping_add(dy))))
+            .filter(|&(_, (row, col))| {
+                row < self.rows && col < self.cols && !is_blocked(self.get((row, col)))
+            })
+    }
+
+    /// Renders the maze row by row with `path` painted as `'*'`, without ever
+    /// building the whole grid in memory.
+    pub fn write_solved(
+        &self,
+        path: &[(usize, usize)],
+        out: &mut impl std::io::Write,
+    ) -> std::io::Result<()> {
+        let mut path = path.to_vec();
+        path.sort_unstable();
+        let mut path = path.into_iter().peekable();
+        for row in 0..self.rows {
+            let mut line = String::with_capacity(self.cols);
+            for col in 0..self.cols {
+                let on_path = path.next_if(|&cell| cell == (row, col)).is_some();
+                let c = self.get((row, col));
+                let open = c == ' ' || c.is_ascii_digit();
+                line.push(if on_path && open { '*' } else { c });
+            }
+            writeln!(out, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for MmapMaze {
+    fn drop(&mut self) {
+        // SAFETY: `data`/`len` are exactly what `mmap` returned in `open`.
+        unsafe { munmap(self.data as *mut c_void, self.len) };
+    }
+}
+
+/// A fixed-size array of `BITS`-bit values packed into bytes.
+struct Packed<const BITS: usize> {
+    bytes: Vec<u8>,
+}
+
+impl<const BITS: usize> Packed<BITS> {
+    fn new(len: usize) -> Self {
+        Self {
+            bytes: vec![0; (len * BITS).div_ceil(8)],
+        }
+    }
+
+    fn get(&self, i: usize) -> u8 {
+        let bit = i * BITS;
+        (self.bytes[bit / 8] >> (bit % 8)) & ((1 << BITS) - 1)
+    }
+
+    fn set(&mut self, i: usize, value: u8) {
+        let bit = i * BITS;
+        let mask = ((1u8 << BITS) - 1) << (bit % 8);
+        self.bytes[bit / 8] = (self.bytes[bit / 8] & !mask) | (value << (bit % 8));
+    }
+}
+
+/// Breadth first search over a mapped maze, returning the path from the start
+/// to the end, inclusive of both.
+pub fn bfs(maze: &MmapMaze) -> Result<Option<Vec<(usize, usize)>>, Error> {
+    let start = maze.start().ok_or(Error::StartNotFound)?;
+    let end = maze.end().ok_or(Error::EndNotFound)?;
+    let index = |(row, col): (usize, usize)| row * maze.cols() + col;
+
+    let mut visited = Packed::<1>::new(maze.rows() * maze.cols());
+    let mut came_from = Packed::<2>::new(maze.rows() * maze.cols());
+    let mut frontier = VecDeque::from([start]);
+    visited.set(index(start), 1);
+
+    while let Some(cell) = frontier.pop_front() {
+        if cell == end {
+            let mut path = vec![end];
+            let mut cell = end;
+            while cell != start {
+                // Step back against the direction this cell was entered in.
+                let (dx, dy) = DIRECTIONS[came_from.get(index(cell)) as usize];
+                cell = (cell.0.wrapping_sub(dx), cell.1.wrapping_sub(dy));
+                path.push(cell);
+            }
+            path.reverse();
+            return Ok(Some(path));
+        }
+        for (d, next) in maze.neighbours(cell).collect::<Vec<_>>() {
+            if visited.get(index(next)) == 0 {
+                visited.set(index(next), 1);
+                came_from.set(index(next), d);
+                frontier.push_back(next);
+            }
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_maze, solve, Algorithm};
+
+    const MAZE: &str = "#######\n#A#   #\n# # # #\n#   #B#\n#######\n";
+
+    #[test]
+    fn bfs_matches_the_in_memory_solver() {
+        let path = std::env::temp_dir().join(format!("mmap-test-{}.txt", std::process::id()));
+        std::fs::write(&path, MAZE).unwrap();
+        let maze = MmapMaze::open(path.to_str().unwrap()).unwrap();
+        let found = bfs(&maze).unwrap().unwrap();
+        let expected = solve(&parse_maze(MAZE), Algorithm::BFS).unwrap().unwrap();
+        assert_eq!(found, expected.path);
+
+        let mut out = Vec::new();
+        maze.write_solved(&found, &mut out).unwrap();
+        drop(maze);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "███████\n█A█***█\n█*█*█*█\n█***█B█\n███████\n"
+        );
+    }
+}
@@ -0,0 +1,76 @@
+//! Lightweight phase timers for the solver loops, reported as folded stacks
+//! (`maze_solver;a_star;heuristic 1234`, in nanoseconds) that `inferno` and
+//! `flamegraph.pl` accept directly. Timing is only compiled in with the
+//! `profile` feature; without it [`time`] just calls its closure.
+
+#[cfg(feature = "profile")]
+use std::{cell::RefCell, collections::BTreeMap, time::Instant};
+
+#[cfg(feature = "profile")]
+thread_local! {
+    static TOTALS: RefCell<BTreeMap<(&'static str, &'static str), u128>> =
+        const { RefCell::new(BTreeMap::new()) };
+}
+
+/// Runs `f`, attributing the time it takes to `phase` of `solver`.
+#[cfg(feature = "profile")]
+pub fn time<T>(solver: &'static str, phase: &'static str, f: impl FnOnce() -> T) -> T {
+    let started = Instant::now();
+    let result = f();
+    let elapsed = started.elapsed().as_nanos();
+    TOTALS.with(|totals| *totals.borrow_mut().entry((solver, phase)).or_default() += elapsed);
+    result
+}
+
+#[cfg(not(feature = "profile"))]
+#[inline(always)]
+pub fn time<T>(_: &'static str, _: &'static str, f: impl FnOnce() -> T) -> T {
+    f()
+}
+
+/// The time collected on this thread so far, one folded stack per line.
+#[cfg(feature = "profile")]
+pub fn folded() -> String {
+    TOTALS.with(|totals| {
+        totals
+            .borrow()
+            .iter()
+            .map(|((solver, phase), nanos)| format!("maze_solver;{solver};{phase} {nanos}\n"))
+            .collect()
+    })
+}
+
+/// Discards the time collected on this thread so far.
+#[cfg(feature = "profile")]
+pub fn reset() {
+    TOTALS.with(|totals| totals.borrow_mut().clear());
+}
+
+#[cfg(all(test, feature = "profile"))]
+mod tests {
+    use super::*;
+    use crate::{parse_maze, solve, Algorithm};
+
+    #[test]
+    fn solving_records_each_phase() {
+        let maze = parse_maze("#######\n#A    #\n#  #  #\n#    B#\n#######");
+        reset();
+        solve(&maze, Algorithm::AStar).unwrap();
+        solve(&maze, Algorithm::DFS).unwrap();
+        let folded = folded();
+        let stacks = folded
+            .lines()
+            .map(|line| line.rsplit_once(' ').unwrap().0)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            stacks,
+            [
+                "maze_solver;a_star;frontier",
+                "maze_solver;a_star;heuristic",
+                "maze_solver;a_star;neighbours",
+                "maze_solver;dfs;frontier",
+                "maze_solver;dfs;neighbours",
+            ]
+        );
+    }
+}
@@ -0,0 +1,76 @@
+//! A small deterministic pseudo random generator (splitmix64). It is good
+//! enough for tie breaking and shuffling, not for anything cryptographic.
+//!
+//! Features that need randomness draw their generator from [`Seeds`] rather
+//! than creating one themselves, so a single seed reproduces a whole run.
+
+use std::hash::{BuildHasher, Hasher};
+
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// A generator seeded from the process' hash randomness, different on every call.
+    pub fn from_entropy() -> Self {
+        Self(
+            std::collections::hash_map::RandomState::new()
+                .build_hasher()
+                .finish(),
+        )
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A value in `0..n`.
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// `true` with probability `p`.
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// Where every randomized feature gets its generator from. With a seed each
+/// feature gets its own sub-seed derived from the seed and the feature's name,
+/// so adding randomness to one feature does not change what another draws.
+/// Without one every generator is seeded from entropy.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Seeds(Option<u64>);
+
+impl Seeds {
+    pub fn new(seed: Option<u64>) -> Self {
+        Self(seed)
+    }
+
+    /// A generator for `feature`. Seeded factories return an identically
+    /// seeded generator every time they are asked for the same feature.
+    pub fn rng(self, feature: &str) -> Rng {
+        let Some(seed) = self.0 else {
+            return Rng::from_entropy();
+        };
+        // FNV-1a, so sub-seeds stay the same across builds and platforms.
+        let hash = feature.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+        let mut rng = Rng::new(seed ^ hash);
+        Rng::new(rng.next_u64())
+    }
+}
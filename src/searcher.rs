@@ -0,0 +1,203 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+};
+
+use crate::{
+    cell_cost, manhattan_dist, neighbours, reconstruct_path, rng::Rng, Algorithm, Error, Path,
+};
+
+/// Heap entries are ordered by priority, then by a tie-break key that is
+/// always 0 unless random ties are enabled. The priority is a pair so greedy
+/// best first can prefer cheaper terrain among cells equally close to the
+/// end, like the solver in the crate root.
+type HeapEntry = (Reverse<(usize, usize)>, u64, (usize, usize));
+
+enum Frontier {
+    Queue(VecDeque<(usize, usize)>),
+    Stack(Vec<(usize, usize)>),
+    Heap(BinaryHeap<HeapEntry>),
+}
+
+/// What a single call to [`Searcher::step`] did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    Expanded((usize, usize)),
+    Found(Path),
+    Exhausted,
+    /// The end was reached but its path could not be rebuilt, a searcher bug.
+    Failed(Error),
+}
+
+/// A search that advances one expansion at a time, so a caller can render
+/// the frontier and visited cells between steps. Unlike the solvers in
+/// the crate root, it never writes to the grid.
+pub struct Searcher<'a> {
+    maze: &'a [Vec<char>],
+    algorithm: Algorithm,
+    start: (usize, usize),
+    end: (usize, usize),
+    frontier: Frontier,
+    queued: HashSet<(usize, usize)>,
+    visited: HashSet<(usize, usize)>,
+    came_from: HashMap<(usize, usize), (usize, usize)>,
+    cost: HashMap<(usize, usize), usize>,
+    expansions: usize,
+    path: Option<Path>,
+    failed: Option<Error>,
+    ties: Option<Rng>,
+}
+
+impl<'a> Searcher<'a> {
+    pub fn new(
+        maze: &'a [Vec<char>],
+        algorithm: Algorithm,
+        start: (usize, usize),
+        end: (usize, usize),
+    ) -> Self {
+        let frontier =
+            match algorithm {
+                Algorithm::DFS => Frontier::Stack(vec![start]),
+                Algorithm::BFS => Frontier::Queue(VecDeque::from([start])),
+                Algorithm::GreedyBestFirst | Algorithm::AStar => Frontier::Heap(BinaryHeap::from(
+                    [(Reverse((manhattan_dist(start, end), 1)), 0, start)],
+                )),
+            };
+        Self {
+            maze,
+            algorithm,
+            start,
+            end,
+            frontier,
+            queued: HashSet::from([start]),
+            visited: HashSet::new(),
+            came_from: HashMap::new(),
+            cost: HashMap::from([(start, 0)]),
+            expansions: 0,
+            path: None,
+            failed: None,
+            ties: None,
+        }
+    }
+
+    /// Breaks ties between equally good frontier cells with `rng` instead of
+    /// the fixed neighbour order, making the search nondeterministic unless
+    /// `rng` is seeded.
+    pub fn random_ties(mut self, rng: Rng) -> Self {
+        self.ties = Some(rng);
+        self
+    }
+
+    fn pop(&mut self) -> Option<(usize, usize)> {
+        match &mut self.frontier {
+            Frontier::Queue(queue) => queue.pop_front(),
+            Frontier::Stack(stack) => stack.pop(),
+            Frontier::Heap(heap) => heap.pop().map(|(_, _, cell)| cell),
+        }
+    }
+
+    fn push(&mut self, cell: (usize, usize)) {
+        let priority = match self.algorithm {
+            Algorithm::AStar => (manhattan_dist(cell, self.end) + self.cost[&cell], 0),
+            _ => (
+                manhattan_dist(cell, self.end),
+                cell_cost(self.maze[cell.0][cell.1]),
+            ),
+        };
+        match &mut self.frontier {
+            Frontier::Queue(queue) => queue.push_back(cell),
+            Frontier::Stack(stack) => stack.push(cell),
+            Frontier::Heap(heap) => {
+                let tie = self.ties.as_mut().map_or(0, Rng::next_u64);
+                heap.push((Reverse(priority), tie, cell))
+            }
+        }
+    }
+
+    /// Pops the next frontier cell and expands it. Once the end has been found,
+    /// the frontier is empty or the search failed, further calls keep
+    /// returning the same result.
+    pub fn step(&mut self) -> Step {
+        if let Some(path) = &self.path {
+            return Step::Found(path.clone());
+        }
+        if let Some(error) = self.failed {
+            return Step::Failed(error);
+        }
+        let cell = loop {
+            match self.pop() {
+                None => return Step::Exhausted,
+                Some(cell) if self.visited.contains(&cell) => continue,
+                Some(cell) => break cell,
+            }
+        };
+        if cell == self.end {
+            return match reconstruct_path(&self.came_from, self.start, self.end) {
+                Ok(path) => {
+                    self.path = Some(path.clone());
+                    Step::Found(path)
+                }
+                Err(error) => {
+                    self.failed = Some(error);
+                    Step::Failed(error)
+                }
+            };
+        }
+
+        self.visited.insert(cell);
+        self.expansions += 1;
+        let dfs = matches!(self.algorithm, Algorithm::DFS);
+        let mut next_cells = neighbours(self.maze, cell).collect::<Vec<_>>();
+        if let Some(rng) = &mut self.ties {
+            rng.shuffle(&mut next_cells);
+        } else if dfs {
+            // Reversed so the stack pops neighbours in `DIRECTIONS` order,
+            // the same order the crate root's DFS tries them in.
+            next_cells.reverse();
+        }
+        let a_star = matches!(self.algorithm, Algorithm::AStar);
+        for next in next_cells {
+            let cost = self.cost[&cell] + cell_cost(self.maze[next.0][next.1]);
+            // A* queues a cell again whenever it finds a cheaper way there;
+            // the stale entry is skipped once the cell has been visited.
+            let cheaper = a_star && self.cost.get(&next).is_some_and(|&known| cost < known);
+            if self.visited.contains(&next) || (!dfs && self.queued.contains(&next) && !cheaper) {
+                continue;
+            }
+            self.queued.insert(next);
+            self.came_from.insert(next, cell);
+            self.cost.insert(next, cost);
+            self.push(next);
+        }
+        Step::Expanded(cell)
+    }
+
+    /// Number of cells expanded so far.
+    pub fn expansions(&self) -> usize {
+        self.expansions
+    }
+
+    #[cfg(feature = "tui")]
+    pub fn visited(&self) -> &HashSet<(usize, usize)> {
+        &self.visited
+    }
+
+    /// The cells currently waiting to be expanded.
+    #[cfg(feature = "tui")]
+    pub fn frontier(&self) -> Vec<(usize, usize)> {
+        let cells: Vec<(usize, usize)> = match &self.frontier {
+            Frontier::Queue(queue) => queue.iter().copied().collect(),
+            Frontier::Stack(stack) => stack.clone(),
+            Frontier::Heap(heap) => heap.iter().map(|&(_, _, cell)| cell).collect(),
+        };
+        cells
+            .into_iter()
+            .filter(|cell| !self.visited.contains(cell))
+            .collect()
+    }
+
+    /// The path to the end, once it has been found.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_ref()
+    }
+}
@@ -0,0 +1,177 @@
+//! The non-identity symmetries of a rectangular grid and checks for which of
+//! them leave a maze unchanged.
+
+use crate::{END, ONE_WAY, START};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symmetry {
+    /// Mirror left to right.
+    FlipHorizontal,
+    /// Mirror top to bottom.
+    FlipVertical,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    /// Mirror along the main diagonal.
+    Transpose,
+    /// Mirror along the anti-diagonal.
+    AntiTranspose,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 7] = [
+        Symmetry::FlipHorizontal,
+        Symmetry::FlipVertical,
+        Symmetry::Rotate90,
+        Symmetry::Rotate180,
+        Symmetry::Rotate270,
+        Symmetry::Transpose,
+        Symmetry::AntiTranspose,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Symmetry::FlipHorizontal => "flip-horizontal",
+            Symmetry::FlipVertical => "flip-vertical",
+            Symmetry::Rotate90 => "rotate-90",
+            Symmetry::Rotate180 => "rotate-180",
+            Symmetry::Rotate270 => "rotate-270",
+            Symmetry::Transpose => "transpose",
+            Symmetry::AntiTranspose => "anti-transpose",
+        }
+    }
+
+    /// Whether the transform swaps the number of rows and columns.
+    fn swaps_axes(self) -> bool {
+        matches!(
+            self,
+            Symmetry::Rotate90
+                | Symmetry::Rotate270
+                | Symmetry::Transpose
+                | Symmetry::AntiTranspose
+        )
+    }
+
+    /// Applies the transform to a rectangular grid. Rotations are clockwise.
+    pub fn apply<T: Copy>(self, grid: &[Vec<T>]) -> Vec<Vec<T>> {
+        let (rows, cols) = (grid.len(), grid.first().map_or(0, Vec::len));
+        let (out_rows, out_cols) = if self.swaps_axes() {
+            (cols, rows)
+        } else {
+            (rows, cols)
+        };
+        (0..out_rows)
+            .map(|r| {
+                (0..out_cols)
+                    .map(|c| {
+                        let (sr, sc) = match self {
+                            Symmetry::FlipHorizontal => (r, cols - 1 - c),
+                            Symmetry::FlipVertical => (rows - 1 - r, c),
+                            Symmetry::Rotate90 => (rows - 1 - c, r),
+                            Symmetry::Rotate180 => (rows - 1 - r, cols - 1 - c),
+                            Symmetry::Rotate270 => (c, cols - 1 - r),
+                            Symmetry::Transpose => (c, r),
+                            Symmetry::AntiTranspose => (rows - 1 - c, cols - 1 - r),
+                        };
+                        grid[sr][sc]
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Where the transform sends a one-way arrow, so the arrow keeps pointing
+    /// along the same corridor.
+    pub fn map_arrow(self, arrow: char) -> char {
+        // Indices into `ONE_WAY`: up, left, down, right.
+        let to = match self {
+            Symmetry::FlipHorizontal => [0, 3, 2, 1],
+            Symmetry::FlipVertical => [2, 1, 0, 3],
+            Symmetry::Rotate90 => [3, 0, 1, 2],
+            Symmetry::Rotate180 => [2, 3, 0, 1],
+            Symmetry::Rotate270 => [1, 2, 3, 0],
+            Symmetry::Transpose => [1, 0, 3, 2],
+            Symmetry::AntiTranspose => [3, 2, 1, 0],
+        };
+        ONE_WAY
+            .iter()
+            .position(|&c| c == arrow)
+            .map_or(arrow, |i| ONE_WAY[to[i]])
+    }
+
+    /// Applies the transform to a maze, turning one-way arrows with it.
+    pub fn apply_to_maze(self, maze: &[Vec<char>]) -> Vec<Vec<char>> {
+        let mut out = self.apply(maze);
+        out.iter_mut()
+            .flatten()
+            .for_each(|c| *c = self.map_arrow(*c));
+        out
+    }
+}
+
+/// Every symmetry under which the maze's wall/open pattern is unchanged, with
+/// one-way arrows turned along with the grid. With `include_endpoints` the
+/// start and end must also land on themselves; otherwise they are treated as
+/// open cells.
+pub fn maze_symmetries(maze: &[Vec<char>], include_endpoints: bool) -> Vec<Symmetry> {
+    let pattern = maze
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|&c| match c {
+                    START | END if !include_endpoints => ' ',
+                    c => c,
+                })
+                .collect()
+        })
+        .collect::<Vec<Vec<char>>>();
+    let square = pattern.len() == pattern.first().map_or(0, Vec::len);
+
+    Symmetry::ALL
+        .into_iter()
+        .filter(|s| square || !s.swaps_axes())
+        .filter(|s| s.apply_to_maze(&pattern) == pattern)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{maze_solver, parse_maze, render, Algorithm};
+
+    #[test]
+    fn symmetric_and_asymmetric_mazes() {
+        let symmetric = parse_maze("#####\n#A  #\n# # #\n#  B#\n#####");
+        assert_eq!(maze_symmetries(&symmetric, false), Symmetry::ALL);
+        assert_eq!(maze_symmetries(&symmetric, true), [Symmetry::Transpose]);
+
+        let asymmetric = parse_maze("#####\n### #\n#A  #\n#  B#\n#####");
+        assert_eq!(maze_symmetries(&asymmetric, false), []);
+
+        // One-way arrows have to turn with the maze to match.
+        let facing = parse_maze("#A> <B#");
+        assert_eq!(
+            maze_symmetries(&facing, false),
+            [
+                Symmetry::FlipHorizontal,
+                Symmetry::FlipVertical,
+                Symmetry::Rotate180
+            ]
+        );
+        let same_way = parse_maze("#A> >B#");
+        assert_eq!(maze_symmetries(&same_way, false), [Symmetry::FlipVertical]);
+    }
+
+    #[test]
+    fn opposite_rotations_cancel() {
+        let mut maze = parse_maze("#######\n#A  > #\n#  # B#\n#  v  #\n#######");
+        maze_solver(&mut maze, Algorithm::BFS, true).unwrap();
+        let solved = render(&maze);
+        assert!(solved.contains('*') && solved.contains('@'));
+
+        let rotated = Symmetry::Rotate90.apply_to_maze(&maze);
+        assert_eq!((rotated.len(), rotated[0].len()), (7, 5));
+        assert_ne!(render(&rotated), solved);
+        assert_eq!(render(&Symmetry::Rotate270.apply_to_maze(&rotated)), solved);
+    }
+}
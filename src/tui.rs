@@ -0,0 +1,214 @@
+//! Interactive stepping through a search, drawn with plain ANSI escapes.
+//! Space advances the search by one expansion, the arrow keys pan the view
+//! over mazes larger than the terminal and `q` quits.
+
+use std::{
+    io::{Read, Write},
+    process::{Command, Stdio},
+    time::{Duration, Instant},
+};
+
+use crate::{searcher::Searcher, Error};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Space,
+    Up,
+    Down,
+    Left,
+    Right,
+    Quit,
+}
+
+/// The state behind the terminal: the search and the top-left corner of the
+/// visible window. Kept separate from the terminal so it can be driven
+/// without one.
+pub struct Session<'a> {
+    pub searcher: Searcher<'a>,
+    maze: &'a [Vec<char>],
+    offset: (usize, usize),
+}
+
+impl<'a> Session<'a> {
+    pub fn new(maze: &'a [Vec<char>], searcher: Searcher<'a>) -> Self {
+        Self {
+            searcher,
+            maze,
+            offset: (0, 0),
+        }
+    }
+
+    /// Applies `key`, returning `false` once the session should end.
+    pub fn handle(&mut self, key: Key) -> bool {
+        let (rows, cols) = (self.maze.len(), self.maze[0].len());
+        match key {
+            Key::Space => _ = self.searcher.step(),
+            Key::Up => self.offset.0 = self.offset.0.saturating_sub(1),
+            Key::Down => self.offset.0 = (self.offset.0 + 1).min(rows - 1),
+            Key::Left => self.offset.1 = self.offset.1.saturating_sub(1),
+            Key::Right => self.offset.1 = (self.offset.1 + 1).min(cols - 1),
+            Key::Quit => return false,
+        }
+        true
+    }
+
+    /// The visible `height` x `width` window of the maze with the search state
+    /// painted over it: `'+'` for the frontier, `'@'` for visited cells and
+    /// `'*'` for the path once found.
+    pub fn frame(&self, height: usize, width: usize) -> String {
+        let mut grid = self.maze.to_vec();
+        let mut paint = |(row, col): (usize, usize), c: char| {
+            if grid[row][col] == ' ' {
+                grid[row][col] = c;
+            }
+        };
+        self.searcher
+            .visited()
+            .iter()
+            .for_each(|&cell| paint(cell, '@'));
+        self.searcher
+            .frontier()
+            .into_iter()
+            .for_each(|cell| paint(cell, '+'));
+        for &(row, col) in self.searcher.path().into_iter().flatten() {
+            if matches!(grid[row][col], '@' | '+') {
+                grid[row][col] = '*';
+            }
+        }
+
+        grid.iter()
+            .skip(self.offset.0)
+            .take(height)
+            .map(|row| {
+                row.iter()
+                    .skip(self.offset.1)
+                    .take(width)
+                    .collect::<String>()
+                    + "\r\n"
+            })
+            .collect()
+    }
+}
+
+/// The live statistics line drawn under the maze.
+pub fn footer(expansions: usize, frontier: usize, elapsed: Duration) -> String {
+    format!(
+        "expansions={expansions} frontier={frontier} elapsed={:.3}s",
+        elapsed.as_secs_f64()
+    )
+}
+
+fn stty(args: &[&str]) -> Option<String> {
+    let out = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()
+        .ok()?;
+    out.status
+        .success()
+        .then(|| String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+fn terminal_size() -> (usize, usize) {
+    stty(&["size"])
+        .and_then(|size| {
+            let mut it = size.split_whitespace().map(str::parse::<usize>);
+            Some((it.next()?.ok()?, it.next()?.ok()?))
+        })
+        .filter(|&(rows, cols)| rows > 1 && cols > 0)
+        .unwrap_or((24, 80))
+}
+
+fn read_key(stdin: &mut impl Read) -> Option<Key> {
+    let mut byte = [0u8];
+    loop {
+        stdin.read_exact(&mut byte).ok()?;
+        match byte[0] {
+            b' ' => return Some(Key::Space),
+            b'q' => return Some(Key::Quit),
+            0x1b => {
+                let mut seq = [0u8; 2];
+                stdin.read_exact(&mut seq).ok()?;
+                match seq {
+                    [b'[', b'A'] => return Some(Key::Up),
+                    [b'[', b'B'] => return Some(Key::Down),
+                    [b'[', b'C'] => return Some(Key::Right),
+                    [b'[', b'D'] => return Some(Key::Left),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Runs the interactive session until `q` is pressed or stdin closes. With
+/// `live_stats` the bottom line of the terminal shows the expansion count,
+/// frontier size and time since the session started, rewritten in place
+/// after every key.
+pub fn run(mut session: Session, live_stats: bool) -> Result<(), Error> {
+    stty(&["raw", "-echo"]).ok_or(Error::TerminalFailed)?;
+    let (height, width) = terminal_size();
+    let started = Instant::now();
+    let mut stdin = std::io::stdin().lock();
+    let mut stdout = std::io::stdout().lock();
+    let result = loop {
+        let status = match session.searcher.path() {
+            Some(path) => format!("found, path={}", path.len() - 1),
+            None => format!("expansions={}", session.searcher.expansions()),
+        };
+        let footer = match live_stats {
+            true => format!(
+                "\x1b[{height};1H\x1b[2K{}",
+                footer(
+                    session.searcher.expansions(),
+                    session.searcher.frontier().len(),
+                    started.elapsed()
+                )
+            ),
+            false => String::new(),
+        };
+        let frame = session.frame(height.saturating_sub(1 + usize::from(live_stats)), width);
+        if write!(stdout, "\x1b[2J\x1b[H{frame}{status}\r\n{footer}")
+            .and_then(|_| stdout.flush())
+            .is_err()
+        {
+            break Err(Error::TerminalFailed);
+        }
+        match read_key(&mut stdin) {
+            Some(key) if session.handle(key) => {}
+            _ => break Ok(()),
+        }
+    };
+    stty(&["sane"]);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{get_end, get_start, parse_maze, Algorithm};
+
+    #[test]
+    fn each_space_expands_one_cell() {
+        let maze = parse_maze("#######\n#A    #\n#     #\n#    B#\n#######");
+        let (start, end) = (get_start(&maze).unwrap(), get_end(&maze).unwrap());
+        let mut session = Session::new(&maze, Searcher::new(&maze, Algorithm::BFS, start, end));
+        for presses in 1..=5 {
+            assert!(session.handle(Key::Space));
+            assert_eq!(session.searcher.expansions(), presses);
+        }
+    }
+
+    #[test]
+    fn footer_formats_the_live_stats() {
+        assert_eq!(
+            footer(42, 7, Duration::from_millis(1500)),
+            "expansions=42 frontier=7 elapsed=1.500s"
+        );
+        assert_eq!(
+            footer(0, 1, Duration::ZERO),
+            "expansions=0 frontier=1 elapsed=0.000s"
+        );
+    }
+}
@@ -0,0 +1,72 @@
+//! An entry point for running the solver in the browser, working purely on
+//! strings with no file or terminal access. Build it with
+//!
+//! ```text
+//! cargo rustc --lib --release --features wasm \
+//!     --target wasm32-unknown-unknown --crate-type cdylib
+//! ```
+//!
+//! The `maze_*` functions are the raw exports for JavaScript: allocate the
+//! input with [`maze_alloc`], call [`maze_solve`], read the result and hand
+//! every buffer back to [`maze_free`].
+
+use crate::{is_maze_valid, json, maze_solver, parse_algorithm, parse_maze, render, Error};
+
+fn try_solve(input: &str, algorithm: &str, display_visited: bool) -> Result<String, Error> {
+    let mut grid = parse_maze(input);
+    is_maze_valid(&grid)?;
+    maze_solver(&mut grid, parse_algorithm(algorithm)?, display_visited)?;
+    Ok(render(&grid))
+}
+
+/// The solved maze as the command line prints it, or `{"error":"<Error>"}` if
+/// the maze or algorithm is invalid or there is no path.
+pub fn solve(input: &str, algorithm: &str, display_visited: bool) -> String {
+    try_solve(input, algorithm, display_visited)
+        .unwrap_or_else(|e| format!("{{\"error\":{}}}", json::string(&format!("{e:?}"))))
+}
+
+/// A zeroed buffer of `len` bytes owned by the caller until [`maze_free`].
+#[no_mangle]
+pub extern "C" fn maze_alloc(len: usize) -> *mut u8 {
+    Box::into_raw(vec![0u8; len].into_boxed_slice()).cast()
+}
+
+/// Releases a buffer from [`maze_alloc`] or [`maze_solve`].
+///
+/// # Safety
+///
+/// `ptr` and `len` must describe a buffer returned by one of those functions
+/// that has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn maze_free(ptr: *mut u8, len: usize) {
+    drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(ptr, len)));
+}
+
+/// [`solve`] over raw UTF-8 buffers. Returns a new buffer holding the result
+/// and writes its length to `out_len`.
+///
+/// # Safety
+///
+/// The input pointers must be valid for reads of their lengths and
+/// `out_len` must be valid for a write.
+#[no_mangle]
+pub unsafe extern "C" fn maze_solve(
+    input: *const u8,
+    input_len: usize,
+    algorithm: *const u8,
+    algorithm_len: usize,
+    display_visited: bool,
+    out_len: *mut usize,
+) -> *mut u8 {
+    let text = |ptr, len| String::from_utf8_lossy(std::slice::from_raw_parts(ptr, len));
+    let result = solve(
+        &text(input, input_len),
+        &text(algorithm, algorithm_len),
+        display_visited,
+    )
+    .into_bytes()
+    .into_boxed_slice();
+    *out_len = result.len();
+    Box::into_raw(result).cast()
+}
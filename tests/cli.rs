@@ -0,0 +1,197 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use maze_solver_rs::{
+    get_end, get_start, parse_maze,
+    rng::Seeds,
+    searcher::{Searcher, Step},
+    Algorithm,
+};
+
+const OPEN: &str = "\
+######
+#A   #
+#    #
+#   B#
+######
+";
+
+/// A fresh scratch directory for one test, so parallel tests don't share files.
+fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("maze-solver-rs-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write(dir: &Path, name: &str, contents: &str) -> String {
+    let path = dir.join(name);
+    std::fs::write(&path, contents).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_maze-solver-rs"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn batch_skips_files_in_the_checkpoint() {
+    let dir = scratch("checkpoint");
+    let first = write(&dir, "first.txt", OPEN);
+    let second = write(&dir, "second.txt", OPEN);
+    let checkpoint = write(&dir, "checkpoint", &format!("{first}\n"));
+
+    let output = run(&[
+        "batch",
+        "BFS",
+        "false",
+        "--checkpoint",
+        &checkpoint,
+        &first,
+        &second,
+    ]);
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(!stdout.contains(&first));
+    assert!(stdout.contains(&second));
+    let done = std::fs::read_to_string(&checkpoint).unwrap();
+    assert_eq!(done.lines().collect::<Vec<_>>(), [&first, &second]);
+}
+
+#[test]
+fn jsonl_prints_one_object_per_file() {
+    let dir = scratch("jsonl");
+    let open = write(&dir, "open.txt", OPEN);
+    let corridor = write(&dir, "corridor.txt", "#####\n#A B#\n#####\n");
+
+    let output = run(&[
+        "batch", "A*", "false", "--format", "jsonl", &open, &corridor,
+    ]);
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines = stdout.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 2);
+    for (line, (file, length)) in lines.iter().zip([(&open, 5), (&corridor, 2)]) {
+        let prefix = format!("{{\"file\":\"{file}\",\"solved\":true,\"path_length\":{length},");
+        assert!(line.starts_with(&prefix), "{line}");
+        let elapsed = line[prefix.len()..]
+            .strip_prefix("\"elapsed_ms\":")
+            .and_then(|rest| rest.strip_suffix('}'))
+            .unwrap();
+        assert!(elapsed.parse::<f64>().is_ok(), "{line}");
+    }
+}
+
+/// Steps a search that breaks ties with the `random-ties` generator of
+/// `seed`, returning every expanded cell in order, then the path.
+fn random_tie_search(maze: &[Vec<char>], seed: u64) -> [Vec<(usize, usize)>; 2] {
+    let (start, end) = (get_start(maze).unwrap(), get_end(maze).unwrap());
+    let rng = Seeds::new(Some(seed)).rng("random-ties");
+    let mut searcher = Searcher::new(maze, Algorithm::DFS, start, end).random_ties(rng);
+    let mut expanded = vec![];
+    loop {
+        match searcher.step() {
+            Step::Expanded(cell) => expanded.push(cell),
+            Step::Found(path) => return [expanded, path],
+            step => panic!("{step:?}"),
+        }
+    }
+}
+
+#[test]
+fn the_same_seed_reproduces_every_randomized_feature() {
+    let generate = |seed: &str| {
+        let output = run(&[
+            "generate",
+            "15",
+            "21",
+            "--difficulty",
+            "easy",
+            "--seed",
+            seed,
+        ]);
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let maze = generate("42");
+    assert_eq!(generate("42"), maze);
+    assert_ne!(generate("43"), maze);
+
+    let maze = parse_maze(&maze);
+    let first = random_tie_search(&maze, 42);
+    assert_eq!(random_tie_search(&maze, 42), first);
+    assert_ne!(random_tie_search(&maze, 43)[0], first[0]);
+}
+
+#[test]
+fn mmap_reports_unsolvable_mazes() {
+    let dir = scratch("mmap");
+    let blocked = write(&dir, "blocked.txt", "#####\n#A#B#\n#####\n");
+
+    let output = run(&[&blocked, "BFS", "false", "--mmap"]);
+
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "█████\n█A█B█\n█████\n"
+    );
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("no path from A to B"));
+}
+
+#[test]
+fn diagonal_is_rejected_by_four_neighbour_modes() {
+    let dir = scratch("diagonal");
+    let open = write(&dir, "open.txt", OPEN);
+    for mode in [
+        &["BFS", "false", "--rings"][..],
+        &["--analyze"],
+        &["--exact-steps", "3"],
+        &["--break-walls"],
+        &["--dead-ends"],
+        &["--symmetry"],
+    ] {
+        let output = run(&[&[open.as_str(), "--diagonal"], mode].concat());
+        assert!(!output.status.success(), "{mode:?}");
+        assert!(String::from_utf8(output.stderr)
+            .unwrap()
+            .contains("InvalidArgument"));
+    }
+    assert!(run(&[&open, "BFS", "false", "--diagonal"]).status.success());
+}
+
+#[test]
+fn min_width_measures_the_printed_path() {
+    let dir = scratch("min-width");
+    let maze = write(
+        &dir,
+        "maze.txt",
+        "#######\n#A  # #\n#     #\n#  #  #\n#    B#\n#######\n",
+    );
+    for (prefer, width) in [("straight", 1), ("turns", 2)] {
+        let output = run(&[&maze, "BFS", "false", "--prefer", prefer, "--min-width"]);
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(
+            stdout.ends_with(&format!("min corridor width: {width}\n")),
+            "{stdout}"
+        );
+    }
+}
+
+#[test]
+fn batch_fails_when_a_file_cannot_be_loaded() {
+    let dir = scratch("load-failure");
+    let open = write(&dir, "open.txt", OPEN);
+    let missing = dir.join("missing.txt").to_str().unwrap().to_string();
+    for format in ["text", "jsonl"] {
+        let output = run(&["batch", "BFS", "false", "--format", format, &missing, &open]);
+        assert!(!output.status.success(), "{format}");
+        assert!(String::from_utf8(output.stdout).unwrap().contains(&open));
+    }
+}
@@ -0,0 +1,44 @@
+#![cfg(feature = "wasm")]
+
+use std::process::Command;
+
+use maze_solver_rs::wasm;
+
+const MAZE: &str = "\
+#######
+#A  # #
+# #   #
+#   #B#
+#######
+";
+
+#[test]
+fn solve_matches_the_command_line() {
+    let path = std::env::temp_dir().join(format!("wasm-test-{}.txt", std::process::id()));
+    std::fs::write(&path, MAZE).unwrap();
+    for algorithm in ["A*", "BFS", "DFS", "GBFS"] {
+        for display_visited in [false, true] {
+            let output = Command::new(env!("CARGO_BIN_EXE_maze-solver-rs"))
+                .args([
+                    path.to_str().unwrap(),
+                    algorithm,
+                    &display_visited.to_string(),
+                ])
+                .output()
+                .unwrap();
+            let stdout = String::from_utf8(output.stdout).unwrap();
+            let (grid, _stats) = stdout.split_once("\n\n").unwrap();
+            assert_eq!(
+                wasm::solve(MAZE, algorithm, display_visited),
+                format!("{grid}\n"),
+                "{algorithm} {display_visited}"
+            );
+        }
+    }
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(
+        wasm::solve(MAZE, "Dijkstra", false),
+        "{\"error\":\"InvalidAlgorithm\"}"
+    );
+}